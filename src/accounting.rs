@@ -0,0 +1,166 @@
+use cosmwasm_std::{Addr, Deps, StdError, StdResult};
+use provwasm_std::ProvenanceQuery;
+use std::convert::TryInto;
+
+use crate::state::{asset_exchange_storage_read, called_capital_storage_read, config_read};
+
+// commitment still held by the sub, adjusted by any exchanges not yet completed
+pub fn remaining_commitment(deps: Deps<ProvenanceQuery>, subscription: &Addr) -> StdResult<u64> {
+    let state = config_read(deps.storage).load()?;
+
+    let balance: i128 = deps
+        .querier
+        .query_balance(subscription.as_str(), state.commitment_denom)?
+        .amount
+        .u128()
+        .try_into()
+        .map_err(|_| StdError::generic_err("commitment balance overflow"))?;
+
+    let pending: i128 = asset_exchange_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|exchange| exchange.commitment_in_shares)
+        .map(i128::from)
+        .sum();
+
+    // pending calls beyond the held balance leave nothing remaining
+    (balance + pending)
+        .max(0)
+        .try_into()
+        .map_err(|_| StdError::generic_err("remaining commitment overflow"))
+}
+
+// commitment taken on at acceptance net of reductions, whether or not it has been called
+pub fn committed_shares(deps: Deps<ProvenanceQuery>, subscription: &Addr) -> StdResult<u64> {
+    let state = config_read(deps.storage).load()?;
+    let called = called_capital_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+
+    remaining_commitment(deps, subscription)?
+        .checked_add(state.capital_to_shares(called)?)
+        .ok_or_else(|| StdError::generic_err("committed shares overflow"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::tests::default_deps;
+    use crate::msg::AssetExchange;
+    use crate::state::{asset_exchange_storage, called_capital_storage};
+    use cosmwasm_std::coins;
+
+    fn commitment_exchange(commitment_in_shares: i64) -> AssetExchange {
+        AssetExchange {
+            investment: None,
+            commitment_in_shares: Some(commitment_in_shares),
+            capital: None,
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn remaining_commitment_from_balance() {
+        let mut deps = default_deps(None);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(5_000, "commitment_coin"));
+
+        assert_eq!(
+            5_000,
+            remaining_commitment(deps.as_ref(), &Addr::unchecked("sub_1")).unwrap()
+        );
+    }
+
+    #[test]
+    fn remaining_commitment_with_mixed_exchanges() {
+        let mut deps = default_deps(None);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(5_000, "commitment_coin"));
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![
+                    commitment_exchange(-2_000),
+                    commitment_exchange(500),
+                    AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                    commitment_exchange(-1_000),
+                ],
+            )
+            .unwrap();
+
+        // verify only commitment adjustments are folded into the balance
+        assert_eq!(
+            2_500,
+            remaining_commitment(deps.as_ref(), &Addr::unchecked("sub_1")).unwrap()
+        );
+    }
+
+    #[test]
+    fn remaining_commitment_pending_increase_without_balance() {
+        let mut deps = default_deps(None);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![commitment_exchange(1_000)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            1_000,
+            remaining_commitment(deps.as_ref(), &Addr::unchecked("sub_1")).unwrap()
+        );
+    }
+
+    #[test]
+    fn remaining_commitment_overdrawn() {
+        let mut deps = default_deps(None);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(500, "commitment_coin"));
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![commitment_exchange(-1_000)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            0,
+            remaining_commitment(deps.as_ref(), &Addr::unchecked("sub_1")).unwrap()
+        );
+    }
+
+    #[test]
+    fn committed_shares_include_called_capital() {
+        let mut deps = default_deps(None);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(750, "commitment_coin"));
+        called_capital_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &25_000)
+            .unwrap();
+
+        assert_eq!(
+            1_000,
+            committed_shares(deps.as_ref(), &Addr::unchecked("sub_1")).unwrap()
+        );
+    }
+}
@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccreditationQueryMsg {
+    GetAccreditations {},
+}
@@ -1,12 +1,42 @@
 use crate::error::contract_error;
 use crate::exchange_asset::try_cancel_asset_exchanges;
+use crate::exchange_asset::try_cancel_redemptions_older_than;
+use crate::exchange_asset::try_claim_distribution;
 use crate::exchange_asset::try_complete_asset_exchange;
+use crate::exchange_asset::try_emergency_halt;
+use crate::exchange_asset::try_emit_statement;
 use crate::exchange_asset::try_issue_asset_exchanges;
+use crate::exchange_asset::try_issue_capital_calls;
+use crate::exchange_asset::try_issue_distributions;
+use crate::exchange_asset::try_issue_proportional_redemptions;
+use crate::exchange_asset::try_issue_redemptions_by_commitment_pct;
+use crate::exchange_asset::try_notice_capital_call;
+use crate::exchange_asset::try_reduce_commitment;
+use crate::exchange_asset::try_reemit_redemption_events;
+use crate::exchange_asset::try_set_availability_for_subscriptions;
+use crate::exchange_asset::try_set_lockup;
+use crate::exchange_asset::try_set_redemption_cap;
+use crate::exchange_asset::try_set_redemption_note;
+use crate::exchange_asset::try_set_transfer_restriction;
+use crate::exchange_asset::try_sweep_expired_redemptions;
 use crate::state::eligible_subscriptions;
+use crate::state::frozen_subscriptions;
+use crate::state::last_activity;
 use crate::state::pending_subscriptions;
+use crate::state::proposed_commitment_storage;
+use crate::state::{lp_to_subscription, lp_to_subscription_read};
+use crate::state::{pending_commitment, pending_commitment_read};
+use crate::state::{pending_gp, pending_gp_read};
+use crate::state::{pending_lp, pending_lp_read};
 use crate::subscribe::try_accept_subscriptions;
+use crate::subscribe::try_accept_with_redemption_schedule;
 use crate::subscribe::try_close_subscriptions;
 use crate::subscribe::try_propose_subscription;
+use crate::subscribe::try_reevaluate_all_pending;
+use crate::subscribe::try_repair_membership;
+use crate::subscribe::try_requeue_subscriptions;
+use crate::subscribe::try_update_accreditations;
+use crate::subscribe::try_withdraw_proposal;
 use cosmwasm_std::to_binary;
 use cosmwasm_std::WasmMsg;
 use cosmwasm_std::{
@@ -16,35 +46,69 @@ use cosmwasm_std::{
 use provwasm_std::ProvenanceMsg;
 use provwasm_std::ProvenanceQuery;
 use serde::Serialize;
+use std::collections::HashSet;
 
 use crate::error::ContractError;
-use crate::msg::HandleMsg;
+use crate::msg::{ActionKind, HandleMsg};
 use crate::state::config;
+use crate::state::LastActivity;
 
 pub type ContractResponse = Result<Response<ProvenanceMsg>, ContractError>;
 
 #[entry_point]
 pub fn reply(deps: DepsMut<ProvenanceQuery>, _env: Env, msg: Reply) -> ContractResponse {
-    // look for a contract address from instantiating subscription contract
-    if let SubMsgResult::Ok(response) = msg.result {
-        if let Some(contract_address) = contract_address(&response.events) {
-            let eligible = msg.id == 1;
-            let mut storage = if eligible {
-                eligible_subscriptions(deps.storage)
+    match msg.result {
+        // look for a contract address from instantiating subscription contract
+        SubMsgResult::Ok(response) => {
+            let contract_address = contract_address(&response.events).or_else(|| {
+                response
+                    .data
+                    .as_ref()
+                    .and_then(|data| contract_address_from_data(data.as_slice()))
+            });
+            if let Some(contract_address) = contract_address {
+                let eligible = msg.id == 1;
+                let mut storage = if eligible {
+                    eligible_subscriptions(deps.storage)
+                } else {
+                    pending_subscriptions(deps.storage)
+                };
+                let mut subscriptions = storage.may_load()?.unwrap_or_default();
+                subscriptions.insert(contract_address.clone());
+                storage.save(&subscriptions)?;
+
+                if let Some(commitment) = pending_commitment_read(deps.storage).may_load()? {
+                    proposed_commitment_storage(deps.storage)
+                        .save(contract_address.as_bytes(), &commitment)?;
+                    pending_commitment(deps.storage).remove();
+                }
+
+                if let Some(lp) = pending_lp_read(deps.storage).may_load()? {
+                    let mut owned = lp_to_subscription_read(deps.storage)
+                        .may_load(lp.as_bytes())?
+                        .unwrap_or_default();
+                    owned.insert(contract_address.clone());
+                    lp_to_subscription(deps.storage).save(lp.as_bytes(), &owned)?;
+                    pending_lp(deps.storage).remove();
+                }
+
+                Ok(Response::new()
+                    .add_attribute("subscription_address", contract_address.as_str())
+                    .set_data(to_binary(&contract_address)?))
             } else {
-                pending_subscriptions(deps.storage)
-            };
-            let mut subscriptions = storage.may_load()?.unwrap_or_default();
-            subscriptions.insert(contract_address);
-            storage.save(&subscriptions)?;
-        } else {
-            return contract_error("no contract address found");
+                contract_error("no contract address found")
+            }
         }
-    } else {
-        return contract_error("subscription contract instantiation failed");
-    }
+        SubMsgResult::Err(error) => {
+            // nothing was created, so drop what the proposal held for the reply
+            pending_commitment(deps.storage).remove();
+            pending_lp(deps.storage).remove();
 
-    Ok(Response::default())
+            Ok(Response::new()
+                .add_attribute("action", "subscription_instantiate_failed")
+                .add_attribute("error", error))
+        }
+    }
 }
 
 fn contract_address(events: &[Event]) -> Option<Addr> {
@@ -57,22 +121,56 @@ fn contract_address(events: &[Event]) -> Option<Addr> {
     })
 }
 
+// instantiate reply data is a MsgInstantiateContractResponse with the address as field 1
+fn contract_address_from_data(data: &[u8]) -> Option<Addr> {
+    if data.first() != Some(&0x0a) {
+        return None;
+    }
+    let mut len: usize = 0;
+    let mut pos = 1;
+    for shift in (0..35).step_by(7) {
+        let byte = *data.get(pos)?;
+        pos += 1;
+        len |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            let address = std::str::from_utf8(data.get(pos..pos.checked_add(len)?)?).ok()?;
+            return Some(Addr::unchecked(address));
+        }
+    }
+    None
+}
+
 #[derive(Serialize)]
 struct EmptyArgs {}
 
 #[entry_point]
 pub fn execute(
-    deps: DepsMut<ProvenanceQuery>,
+    mut deps: DepsMut<ProvenanceQuery>,
     env: Env,
     info: MessageInfo,
     msg: HandleMsg,
 ) -> ContractResponse {
-    match msg {
+    let block = env.block.clone();
+
+    let state = config(deps.storage).load()?;
+    // only the emergency switch itself gets through while paused
+    if state.paused && !matches!(msg, HandleMsg::SetContractPaused { .. }) {
+        return Err(ContractError::paused("contract is paused"));
+    }
+
+    if let Some(action) = msg.action_kind() {
+        if state.paused_actions.contains(&action) {
+            return Err(ContractError::paused("action is paused"));
+        }
+    }
+
+    let mutates_state = msg.mutates_state();
+    let response = match msg {
         HandleMsg::Recover { gp } => {
             let mut state = config(deps.storage).load()?;
 
             if info.sender != state.recovery_admin {
-                return contract_error("only admin can recover raise");
+                return Err(ContractError::unauthorized("only admin can recover raise"));
             }
 
             state.gp = gp;
@@ -80,6 +178,80 @@ pub fn execute(
 
             Ok(Response::default())
         }
+        HandleMsg::ProposeGp { new_gp } => {
+            let state = config(deps.storage).load()?;
+
+            if info.sender != state.gp {
+                return Err(ContractError::unauthorized("only gp can propose a new gp"));
+            }
+
+            pending_gp(deps.storage).save(&new_gp)?;
+
+            Ok(Response::default())
+        }
+        HandleMsg::AcceptGp {} => {
+            let mut state = config(deps.storage).load()?;
+
+            // the proposed gp must prove control of its address before taking over
+            match pending_gp_read(deps.storage).may_load()? {
+                Some(new_gp) if new_gp == info.sender => {}
+                _ => {
+                    return Err(ContractError::unauthorized(
+                        "only the proposed gp can accept",
+                    ))
+                }
+            }
+
+            state.gp = info.sender;
+            config(deps.storage).save(&state)?;
+            pending_gp(deps.storage).remove();
+
+            Ok(Response::default())
+        }
+        HandleMsg::TransferGp { new_gp } => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.gp && info.sender != state.recovery_admin {
+                return Err(ContractError::unauthorized(
+                    "only gp or admin can transfer gp",
+                ));
+            }
+
+            let old_gp = state.gp;
+            state.gp = new_gp.clone();
+            config(deps.storage).save(&state)?;
+            // a proposal made by the previous gp no longer applies
+            pending_gp(deps.storage).remove();
+
+            Ok(Response::new()
+                .add_attribute("action", "transfer_gp")
+                .add_attribute("old_gp", old_gp)
+                .add_attribute("new_gp", new_gp))
+        }
+        HandleMsg::FreezeSubscription {
+            subscription,
+            frozen,
+        } => {
+            let state = config(deps.storage).load()?;
+
+            if info.sender != state.recovery_admin {
+                return Err(ContractError::unauthorized(
+                    "only admin can freeze subscriptions",
+                ));
+            }
+
+            let mut frozen_subs = frozen_subscriptions(deps.storage)
+                .may_load()?
+                .unwrap_or_default();
+            if frozen {
+                frozen_subs.insert(subscription);
+            } else {
+                frozen_subs.remove(&subscription);
+            }
+            frozen_subscriptions(deps.storage).save(&frozen_subs)?;
+
+            Ok(Response::default())
+        }
         HandleMsg::MigrateSubscriptions { subscriptions } => {
             let state = config(deps.storage).load()?;
 
@@ -92,30 +264,128 @@ pub fn execute(
             )
         }
         HandleMsg::ProposeSubscription { initial_commitment } => {
-            try_propose_subscription(deps, env, info, initial_commitment)
+            try_propose_subscription(deps.branch(), env, info, initial_commitment)
         }
         HandleMsg::CloseSubscriptions { subscriptions } => {
-            try_close_subscriptions(deps, info, subscriptions)
+            try_close_subscriptions(deps.branch(), info, subscriptions)
+        }
+        HandleMsg::RequeueSubscriptions { subscriptions } => {
+            try_requeue_subscriptions(deps.branch(), info, subscriptions)
         }
+        HandleMsg::ReevaluateAllPending {} => try_reevaluate_all_pending(deps.branch(), env, info),
+        HandleMsg::WithdrawProposal {} => try_withdraw_proposal(deps.branch(), info),
+        HandleMsg::RepairMembership {} => try_repair_membership(deps.branch(), info),
+        HandleMsg::UpdateAccreditations {
+            accreditations,
+            start_after,
+        } => try_update_accreditations(deps.branch(), env, info, accreditations, start_after),
         HandleMsg::AcceptSubscriptions { subscriptions } => {
-            try_accept_subscriptions(deps, info, subscriptions)
+            try_accept_subscriptions(deps.branch(), env, info, subscriptions)
         }
-        HandleMsg::IssueAssetExchanges { asset_exchanges } => {
-            try_issue_asset_exchanges(deps, info, asset_exchanges)
+        HandleMsg::AcceptWithRedemptionSchedule {
+            subscription,
+            commitment_in_capital,
+            redemptions,
+        } => try_accept_with_redemption_schedule(
+            deps.branch(),
+            env,
+            info,
+            subscription,
+            commitment_in_capital,
+            redemptions,
+        ),
+        HandleMsg::IssueAssetExchanges {
+            asset_exchanges,
+            idempotency_key,
+        } => try_issue_asset_exchanges(deps.branch(), env, info, asset_exchanges, idempotency_key),
+        HandleMsg::IssueCapitalCalls { calls } => {
+            try_issue_capital_calls(deps.branch(), env, info, calls)
         }
-        HandleMsg::CancelAssetExchanges { cancellations } => {
-            try_cancel_asset_exchanges(deps, info, cancellations)
+        HandleMsg::CancelAssetExchanges {
+            cancellations,
+            idempotency_key,
+        } => try_cancel_asset_exchanges(deps.branch(), info, cancellations, idempotency_key),
+        HandleMsg::IssueProportionalRedemptions {
+            subscriptions,
+            total_capital,
+        } => try_issue_proportional_redemptions(
+            deps.branch(),
+            env,
+            info,
+            subscriptions,
+            total_capital,
+        ),
+        HandleMsg::IssueRedemptionsByCommitmentPct {
+            subscriptions,
+            pct_bps,
+        } => try_issue_redemptions_by_commitment_pct(
+            deps.branch(),
+            env,
+            info,
+            subscriptions,
+            pct_bps,
+        ),
+        HandleMsg::ReduceCommitment {
+            subscription,
+            reduce_shares,
+        } => try_reduce_commitment(deps.branch(), env, info, subscription, reduce_shares),
+        HandleMsg::EmitStatement { subscription } => {
+            try_emit_statement(deps.branch(), env, info, subscription)
         }
+        HandleMsg::ReemitRedemptionEvents {} => try_reemit_redemption_events(deps.branch(), info),
+        HandleMsg::SetRedemptionNote {
+            subscription,
+            asset,
+            capital,
+            gp_note,
+        } => try_set_redemption_note(deps.branch(), info, subscription, asset, capital, gp_note),
+        HandleMsg::NoticeCapitalCall {
+            subscriptions,
+            amount,
+            effective_epoch_seconds,
+            memo,
+        } => try_notice_capital_call(
+            deps.branch(),
+            info,
+            subscriptions,
+            amount,
+            effective_epoch_seconds,
+            memo,
+        ),
+        HandleMsg::CancelRedemptionsOlderThan {
+            issued_before_epoch_seconds,
+        } => try_cancel_redemptions_older_than(deps.branch(), info, issued_before_epoch_seconds),
+        HandleMsg::SweepExpiredRedemptions {} => {
+            try_sweep_expired_redemptions(deps.branch(), env, info)
+        }
+        HandleMsg::EmergencyHalt {} => try_emergency_halt(deps.branch(), info),
+        HandleMsg::IssueDistributions { distributions } => {
+            try_issue_distributions(deps.branch(), info, distributions)
+        }
+        HandleMsg::ClaimDistribution {} => try_claim_distribution(deps.branch(), env, info),
+        HandleMsg::SetAvailabilityForSubscriptions {
+            subscriptions,
+            available_epoch_seconds,
+        } => try_set_availability_for_subscriptions(
+            deps.branch(),
+            env,
+            info,
+            subscriptions,
+            available_epoch_seconds,
+        ),
         HandleMsg::CompleteAssetExchange {
             exchanges,
             to,
             memo,
-        } => try_complete_asset_exchange(deps, env, info, exchanges, to, memo),
+            subscription,
+        } => {
+            try_complete_asset_exchange(deps.branch(), env, info, exchanges, to, memo, subscription)
+        }
         HandleMsg::IssueWithdrawal { to, amount, memo } => {
             let state = config(deps.storage).load()?;
 
             if info.sender != state.gp {
-                return contract_error("only gp can redeem capital");
+                return Err(ContractError::unauthorized("only gp can redeem capital"));
             }
 
             let send = BankMsg::Send {
@@ -135,7 +405,117 @@ pub fn execute(
 
             Ok(Response::new().add_message(send).add_attributes(attributes))
         }
+        HandleMsg::SetLockup {
+            subscription,
+            lockup_end_epoch_seconds,
+        } => try_set_lockup(deps.branch(), info, subscription, lockup_end_epoch_seconds),
+        HandleMsg::SetRedemptionCap {
+            subscription,
+            max_redemption_capital,
+        } => try_set_redemption_cap(deps.branch(), info, subscription, max_redemption_capital),
+        HandleMsg::SetTransferRestriction {
+            subscription,
+            restricted_until_epoch_seconds,
+        } => try_set_transfer_restriction(
+            deps.branch(),
+            info,
+            subscription,
+            restricted_until_epoch_seconds,
+        ),
+        HandleMsg::LockConfig {} => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.gp {
+                return Err(ContractError::unauthorized("only gp can lock config"));
+            }
+
+            state.ensure_config_unlocked()?;
+            state.config_locked = true;
+            config(deps.storage).save(&state)?;
+
+            Ok(Response::default())
+        }
+        HandleMsg::CloseRaise {} => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.gp {
+                return Err(ContractError::unauthorized("only gp can close raise"));
+            }
+
+            state.closed = true;
+            config(deps.storage).save(&state)?;
+
+            Ok(Response::new().add_attribute("action", "close_raise"))
+        }
+        HandleMsg::SetRedemptionsPaused { paused } => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.gp {
+                return Err(ContractError::unauthorized("only gp can pause redemptions"));
+            }
+
+            state.redemptions_paused = paused;
+            config(deps.storage).save(&state)?;
+
+            Ok(Response::default())
+        }
+        HandleMsg::SetPausedActions { actions } => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.gp {
+                return Err(ContractError::unauthorized("only gp can pause actions"));
+            }
+
+            state.paused_actions = actions;
+            config(deps.storage).save(&state)?;
+
+            Ok(Response::default())
+        }
+        HandleMsg::SetPaused { paused } => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.gp {
+                return Err(ContractError::unauthorized("only gp can pause actions"));
+            }
+
+            state.paused_actions = if paused {
+                ActionKind::all()
+            } else {
+                HashSet::new()
+            };
+            config(deps.storage).save(&state)?;
+
+            Ok(Response::default())
+        }
+        HandleMsg::SetContractPaused { paused } => {
+            let mut state = config(deps.storage).load()?;
+
+            if info.sender != state.recovery_admin {
+                return Err(ContractError::unauthorized("only admin can pause contract"));
+            }
+
+            state.paused = paused;
+            config(deps.storage).save(&state)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "set_contract_paused")
+                .add_attribute("paused", paused.to_string()))
+        }
+    }?;
+
+    // an idempotent replay returns early without touching state
+    let replayed = response
+        .attributes
+        .iter()
+        .any(|attribute| attribute.key == "replayed");
+    if mutates_state && !replayed {
+        last_activity(deps.storage).save(&LastActivity {
+            height: block.height,
+            time: block.time,
+        })?;
     }
+
+    Ok(response)
 }
 
 #[cfg(test)]
@@ -143,11 +523,24 @@ pub mod tests {
     use super::*;
     use crate::mock::msg_at_index;
     use crate::mock::send_args;
+    use crate::msg::AcceptSubscription;
+    use crate::msg::AssetExchange;
+    use crate::msg::QueryMsg;
+    use crate::query::query;
+    use crate::state::asset_exchange_storage;
     use crate::state::config_read;
     use crate::state::eligible_subscriptions_read;
+    use crate::state::frozen_subscriptions_read;
+    use crate::state::lockup_storage_read;
     use crate::state::pending_subscriptions_read;
+    use crate::state::proposed_commitment_storage_read;
+    use crate::state::redemption_cap_storage_read;
+    use crate::state::tests::{set_accepted, set_eligible};
+    use crate::state::transfer_restriction_storage_read;
     use crate::state::State;
+    use cosmwasm_std::from_binary;
     use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::Binary;
     use cosmwasm_std::SubMsgResponse;
     use cosmwasm_std::{Addr, OwnedDeps};
     use provwasm_mocks::{mock_dependencies, ProvenanceMockQuerier};
@@ -202,7 +595,7 @@ pub mod tests {
     fn reply_eligible() {
         let mut deps = default_deps(None);
 
-        reply(
+        let res = reply(
             deps.as_mut(),
             mock_env(),
             Reply {
@@ -228,6 +621,168 @@ pub mod tests {
                 .unwrap()
                 .as_str()
         );
+
+        // verify new subscription address is returned
+        assert_eq!(1, res.attributes.len());
+        assert_eq!(
+            &Attribute::new("subscription_address", "sub_1"),
+            res.attributes.first().unwrap()
+        );
+        assert_eq!(
+            Addr::unchecked("sub_1"),
+            from_binary::<Addr>(&res.data.unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn reply_pending_from_instantiate_data() {
+        let mut deps = default_deps(None);
+        let mut data = vec![0x0a, 5];
+        data.extend_from_slice(b"sub_1");
+
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 0,
+                result: cosmwasm_std::SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(Binary::from(data)),
+                }),
+            },
+        )
+        .unwrap();
+
+        // verify sub lands in pending and not eligible
+        assert!(pending_subscriptions_read(&deps.storage)
+            .load()
+            .unwrap()
+            .contains(&Addr::unchecked("sub_1")));
+        assert_eq!(
+            None,
+            eligible_subscriptions_read(&deps.storage)
+                .may_load()
+                .unwrap()
+        );
+        assert_eq!(
+            &Attribute::new("subscription_address", "sub_1"),
+            res.attributes.first().unwrap()
+        );
+    }
+
+    #[test]
+    fn reply_records_proposed_commitment() {
+        let mut deps = default_deps(None);
+        pending_commitment(&mut deps.storage).save(&20_000).unwrap();
+
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 1,
+                result: cosmwasm_std::SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![
+                        Event::new("contract address").add_attribute("_contract_address", "sub_1")
+                    ],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        // verify proposed commitment is keyed by the new subscription
+        assert_eq!(
+            20_000,
+            proposed_commitment_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            pending_commitment_read(&deps.storage).may_load().unwrap()
+        );
+    }
+
+    #[test]
+    fn reply_records_lp_subscriptions() {
+        let mut deps = default_deps(None);
+        let sub_reply = |sub: &str| Reply {
+            id: 0,
+            result: cosmwasm_std::SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("contract address").add_attribute("_contract_address", sub)],
+                data: None,
+            }),
+        };
+
+        pending_lp(&mut deps.storage)
+            .save(&Addr::unchecked("lp"))
+            .unwrap();
+        reply(deps.as_mut(), mock_env(), sub_reply("sub_2")).unwrap();
+        pending_lp(&mut deps.storage)
+            .save(&Addr::unchecked("lp"))
+            .unwrap();
+        reply(deps.as_mut(), mock_env(), sub_reply("sub_1")).unwrap();
+
+        // verify every subscription of the lp is returned
+        let subscriptions: Vec<Addr> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetSubscriptionForLp {
+                    lp: Addr::unchecked("lp"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_2")],
+            subscriptions
+        );
+        assert_eq!(None, pending_lp_read(&deps.storage).may_load().unwrap());
+    }
+
+    #[test]
+    fn reply_instantiate_failed() {
+        let mut deps = default_deps(None);
+        pending_commitment(&mut deps.storage).save(&20_000).unwrap();
+        pending_lp(&mut deps.storage)
+            .save(&Addr::unchecked("lp"))
+            .unwrap();
+
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 1,
+                result: cosmwasm_std::SubMsgResult::Err(String::from("out of gas")),
+            },
+        )
+        .unwrap();
+
+        // verify failure is reported and nothing is inserted
+        assert_eq!(2, res.attributes.len());
+        assert_eq!(
+            &Attribute::new("action", "subscription_instantiate_failed"),
+            res.attributes.first().unwrap()
+        );
+        assert_eq!(
+            None,
+            eligible_subscriptions_read(&deps.storage)
+                .may_load()
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            pending_subscriptions_read(&deps.storage)
+                .may_load()
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            pending_commitment_read(&deps.storage).may_load().unwrap()
+        );
+        assert_eq!(None, pending_lp_read(&deps.storage).may_load().unwrap());
     }
 
     #[test]
@@ -250,60 +805,763 @@ pub mod tests {
     }
 
     #[test]
-    fn fail_bad_actor_recover() {
+    fn propose_and_accept_gp() {
         let mut deps = default_deps(None);
 
-        let res = execute(
+        execute(
             deps.as_mut(),
             mock_env(),
-            mock_info("bad_actor", &vec![]),
-            HandleMsg::Recover {
-                gp: Addr::unchecked("bad_actor"),
+            mock_info("gp", &[]),
+            HandleMsg::ProposeGp {
+                new_gp: Addr::unchecked("multisig"),
             },
-        );
-        assert!(res.is_err());
+        )
+        .unwrap();
 
-        // verify that gp has NOT been updated
-        let state = config_read(&deps.storage).load().unwrap();
-        assert_eq!("gp", state.gp);
+        // verify gp is unchanged until accepted
+        assert_eq!("gp", config_read(&deps.storage).load().unwrap().gp);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("multisig", &[]),
+            HandleMsg::AcceptGp {},
+        )
+        .unwrap();
+
+        // verify gp has been handed over
+        assert_eq!("multisig", config_read(&deps.storage).load().unwrap().gp);
+        assert_eq!(None, pending_gp_read(&deps.storage).may_load().unwrap());
     }
 
     #[test]
-    fn issue_withdrawal() {
+    fn accept_gp_wrong_address() {
         let mut deps = default_deps(None);
 
-        let res = execute(
+        execute(
             deps.as_mut(),
             mock_env(),
             mock_info("gp", &[]),
-            HandleMsg::IssueWithdrawal {
-                to: Addr::unchecked("omni"),
-                amount: 10_000,
-                memo: None,
+            HandleMsg::ProposeGp {
+                new_gp: Addr::unchecked("multisig"),
             },
         )
         .unwrap();
 
-        // verify that send message is sent
-        assert_eq!(1, res.messages.len());
-        let (to_address, coins) = send_args(msg_at_index(&res, 0));
-        assert_eq!("omni", to_address);
-        assert_eq!(10_000, coins.first().unwrap().amount.u128());
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::AcceptGp {},
+        );
+        assert!(res.is_err());
+        assert_eq!("gp", config_read(&deps.storage).load().unwrap().gp);
     }
 
     #[test]
-    fn issue_withdrawal_bad_actor() {
+    fn transfer_gp() {
         let mut deps = default_deps(None);
 
         let res = execute(
             deps.as_mut(),
             mock_env(),
-            mock_info("bad_actor", &[]),
-            HandleMsg::IssueWithdrawal {
-                to: Addr::unchecked("omni"),
-                amount: 10_000,
-                memo: None,
+            mock_info("gp", &[]),
+            HandleMsg::TransferGp {
+                new_gp: Addr::unchecked("gp_2"),
             },
+        )
+        .unwrap();
+
+        // verify transfer attributes
+        assert_eq!(
+            vec![
+                Attribute::new("action", "transfer_gp"),
+                Attribute::new("old_gp", "gp"),
+                Attribute::new("new_gp", "gp_2"),
+            ],
+            res.attributes
+        );
+
+        // verify gp-only operations move to the new gp
+        assert!(execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetRedemptionsPaused { paused: true },
+        )
+        .is_err());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp_2", &[]),
+            HandleMsg::SetRedemptionsPaused { paused: true },
+        )
+        .unwrap();
+        assert!(
+            config_read(&deps.storage)
+                .load()
+                .unwrap()
+                .redemptions_paused
+        );
+    }
+
+    #[test]
+    fn transfer_gp_as_admin() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            HandleMsg::TransferGp {
+                new_gp: Addr::unchecked("gp_2"),
+            },
+        )
+        .unwrap();
+
+        // verify that gp has been updated
+        assert_eq!("gp_2", config_read(&deps.storage).load().unwrap().gp);
+    }
+
+    #[test]
+    fn transfer_gp_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::TransferGp {
+                new_gp: Addr::unchecked("bad_actor"),
+            },
+        );
+        assert!(res.is_err());
+        assert_eq!("gp", config_read(&deps.storage).load().unwrap().gp);
+    }
+
+    #[test]
+    fn freeze_subscription() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            HandleMsg::FreezeSubscription {
+                subscription: Addr::unchecked("sub_1"),
+                frozen: true,
+            },
+        )
+        .unwrap();
+
+        // verify subscription is frozen
+        assert!(frozen_subscriptions_read(&deps.storage)
+            .load()
+            .unwrap()
+            .contains(&Addr::unchecked("sub_1")));
+    }
+
+    #[test]
+    fn freeze_subscription_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::FreezeSubscription {
+                subscription: Addr::unchecked("sub_1"),
+                frozen: true,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn fail_bad_actor_recover() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &vec![]),
+            HandleMsg::Recover {
+                gp: Addr::unchecked("bad_actor"),
+            },
+        );
+        assert!(res.is_err());
+
+        // verify that gp has NOT been updated
+        let state = config_read(&deps.storage).load().unwrap();
+        assert_eq!("gp", state.gp);
+    }
+
+    #[test]
+    fn issue_withdrawal() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueWithdrawal {
+                to: Addr::unchecked("omni"),
+                amount: 10_000,
+                memo: None,
+            },
+        )
+        .unwrap();
+
+        // verify that send message is sent
+        assert_eq!(1, res.messages.len());
+        let (to_address, coins) = send_args(msg_at_index(&res, 0));
+        assert_eq!("omni", to_address);
+        assert_eq!(10_000, coins.first().unwrap().amount.u128());
+    }
+
+    #[test]
+    fn issue_withdrawal_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::IssueWithdrawal {
+                to: Addr::unchecked("omni"),
+                amount: 10_000,
+                memo: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn set_lockup() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetLockup {
+                subscription: Addr::unchecked("sub_1"),
+                lockup_end_epoch_seconds: 1675209600,
+            },
+        )
+        .unwrap();
+
+        // verify lockup is saved
+        assert_eq!(
+            1675209600,
+            lockup_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn set_lockup_bad_actor() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetLockup {
+                subscription: Addr::unchecked("sub_1"),
+                lockup_end_epoch_seconds: 1675209600,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn set_transfer_restriction() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetTransferRestriction {
+                subscription: Addr::unchecked("sub_1"),
+                restricted_until_epoch_seconds: 1675209600,
+            },
+        )
+        .unwrap();
+
+        // verify restriction is saved
+        assert_eq!(
+            1675209600,
+            transfer_restriction_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn set_transfer_restriction_bad_actor() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetTransferRestriction {
+                subscription: Addr::unchecked("sub_1"),
+                restricted_until_epoch_seconds: 1675209600,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn set_redemption_cap() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetRedemptionCap {
+                subscription: Addr::unchecked("sub_1"),
+                max_redemption_capital: Some(10_000),
+            },
+        )
+        .unwrap();
+
+        // verify cap is saved
+        assert_eq!(
+            10_000,
+            redemption_cap_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+
+        // verify cap can be removed
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetRedemptionCap {
+                subscription: Addr::unchecked("sub_1"),
+                max_redemption_capital: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            None,
+            redemption_cap_storage_read(&deps.storage)
+                .may_load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn set_redemption_cap_bad_actor() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetRedemptionCap {
+                subscription: Addr::unchecked("sub_1"),
+                max_redemption_capital: Some(10_000),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn lock_config() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::LockConfig {},
+        )
+        .unwrap();
+
+        // verify config is locked
+        assert!(config_read(&deps.storage).load().unwrap().config_locked);
+
+        // verify config cannot be changed once locked
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::LockConfig {},
+        );
+        assert!(res.is_err());
+        for msg in [
+            HandleMsg::SetLockup {
+                subscription: Addr::unchecked("sub_1"),
+                lockup_end_epoch_seconds: 1675209600,
+            },
+            HandleMsg::SetRedemptionCap {
+                subscription: Addr::unchecked("sub_1"),
+                max_redemption_capital: Some(10_000),
+            },
+            HandleMsg::SetTransferRestriction {
+                subscription: Addr::unchecked("sub_1"),
+                restricted_until_epoch_seconds: 1675209600,
+            },
+        ] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), msg);
+            assert_eq!(
+                "Generic error: config is locked",
+                res.unwrap_err().to_string()
+            );
+        }
+        assert!(lockup_storage_read(&deps.storage)
+            .may_load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn close_raise() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::CloseRaise {},
+        )
+        .unwrap();
+
+        // verify raise is closed
+        assert!(config_read(&deps.storage).load().unwrap().closed);
+    }
+
+    #[test]
+    fn close_raise_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::CloseRaise {},
+        );
+        assert!(res.is_err());
+
+        // verify raise is NOT closed
+        assert!(!config_read(&deps.storage).load().unwrap().closed);
+    }
+
+    #[test]
+    fn lock_config_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::LockConfig {},
+        );
+        assert!(res.is_err());
+
+        // verify config is NOT locked
+        assert!(!config_read(&deps.storage).load().unwrap().config_locked);
+    }
+
+    #[test]
+    fn set_redemptions_paused() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetRedemptionsPaused { paused: true },
+        )
+        .unwrap();
+
+        // verify redemptions are paused
+        assert!(
+            config_read(&deps.storage)
+                .load()
+                .unwrap()
+                .redemptions_paused
+        );
+
+        // verify proposals are still open
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: Some(100),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn set_paused_actions() {
+        let mut deps = default_deps(None);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(1_000),
+                    commitment_in_shares: None,
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetPausedActions {
+                actions: vec![ActionKind::Accept].into_iter().collect(),
+            },
+        )
+        .unwrap();
+
+        // verify accepts are paused
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![],
+            },
+        );
+        assert!(res.is_err());
+
+        // verify claims are still open
+        let can_perform = |action: ActionKind, sender: &str| -> bool {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::CanPerform {
+                    action,
+                    sender: Addr::unchecked(sender),
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        assert!(!can_perform(ActionKind::Accept, "gp"));
+        assert!(can_perform(ActionKind::Claim, "sub_1"));
+    }
+
+    #[test]
+    fn set_paused_actions_gates_cancels() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetPausedActions {
+                actions: vec![ActionKind::Cancel].into_iter().collect(),
+            },
+        )
+        .unwrap();
+
+        // verify every cancel handler is paused
+        for msg in [
+            HandleMsg::CancelAssetExchanges {
+                cancellations: vec![],
+                idempotency_key: None,
+            },
+            HandleMsg::CancelRedemptionsOlderThan {
+                issued_before_epoch_seconds: 0,
+            },
+            HandleMsg::SweepExpiredRedemptions {},
+        ] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), msg);
+            match res.unwrap_err() {
+                ContractError::Paused { msg } => assert_eq!("action is paused", msg),
+                err => panic!("unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn set_paused_toggles_all_actions() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetPaused { paused: true },
+        )
+        .unwrap();
+
+        // verify proposals are paused
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: Some(100),
+            },
+        );
+        assert!(res.is_err());
+        assert_eq!(
+            ActionKind::all(),
+            config_read(&deps.storage).load().unwrap().paused_actions
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetPaused { paused: false },
+        )
+        .unwrap();
+
+        // verify proposals are open again
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: Some(100),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn set_contract_paused_blocks_all_actions() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        set_accepted(&mut deps.storage, vec!["sub_2"]);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_2").as_bytes(),
+                &vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(100),
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        let propose = || HandleMsg::ProposeSubscription {
+            initial_commitment: Some(100),
+        };
+        let accept = || HandleMsg::AcceptSubscriptions {
+            subscriptions: vec![AcceptSubscription {
+                subscription: Addr::unchecked("sub_1"),
+                commitment_in_capital: 20_000,
+                commitment_in_shares: None,
+                wire_reference: None,
+                max_commitment_override: None,
+            }],
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            HandleMsg::SetContractPaused { paused: true },
+        )
+        .unwrap();
+
+        // verify propose, accept and claim are all rejected
+        for (sender, msg) in [
+            ("lp", propose()),
+            ("gp", accept()),
+            (
+                "sub_2",
+                HandleMsg::CompleteAssetExchange {
+                    exchanges: vec![],
+                    to: None,
+                    memo: None,
+                    subscription: None,
+                },
+            ),
+        ] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg);
+            match res.unwrap_err() {
+                ContractError::Paused { msg } => assert_eq!("contract is paused", msg),
+                err => panic!("unexpected error: {}", err),
+            }
+        }
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            HandleMsg::SetContractPaused { paused: false },
+        )
+        .unwrap();
+
+        // verify proposals and accepts work again
+        execute(deps.as_mut(), mock_env(), mock_info("lp", &[]), propose()).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), accept()).unwrap();
+    }
+
+    #[test]
+    fn set_contract_paused_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetContractPaused { paused: true },
+        );
+        assert!(res.is_err());
+        assert!(!config_read(&deps.storage).load().unwrap().paused);
+    }
+
+    #[test]
+    fn set_paused_actions_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetPausedActions {
+                actions: HashSet::new(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn set_redemptions_paused_bad_actor() {
+        let mut deps = default_deps(None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetRedemptionsPaused { paused: true },
         );
         assert!(res.is_err());
     }
@@ -9,12 +9,102 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
-    #[error("Unauthorized")]
-    Unauthorized {},
+    #[error("{msg}")]
+    Unauthorized { msg: String },
+
+    #[error("{msg}")]
+    NotDivisible { msg: String },
+
+    #[error("{msg}")]
+    SubscriptionNotFound { msg: String },
+
+    #[error("{msg}")]
+    RedemptionNotFound { msg: String },
+
+    #[error("{msg}")]
+    AssetExchangeNotFound { msg: String },
+
+    #[error("{msg}")]
+    DistributionNotFound { msg: String },
+
+    #[error("{msg}")]
+    InsufficientFunds { msg: String },
+
+    #[error("{msg}")]
+    Frozen { msg: String },
+
+    #[error("{msg}")]
+    Paused { msg: String },
+
+    #[error("{msg}")]
+    LimitExceeded { msg: String },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
 
+// variants keep the human readable message so clients can match on either
+impl ContractError {
+    pub fn unauthorized(msg: &str) -> Self {
+        ContractError::Unauthorized {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn not_divisible(msg: &str) -> Self {
+        ContractError::NotDivisible {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn subscription_not_found(msg: &str) -> Self {
+        ContractError::SubscriptionNotFound {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn redemption_not_found(msg: &str) -> Self {
+        ContractError::RedemptionNotFound {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn asset_exchange_not_found(msg: &str) -> Self {
+        ContractError::AssetExchangeNotFound {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn distribution_not_found(msg: &str) -> Self {
+        ContractError::DistributionNotFound {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn insufficient_funds(msg: &str) -> Self {
+        ContractError::InsufficientFunds {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn frozen(msg: &str) -> Self {
+        ContractError::Frozen {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn paused(msg: &str) -> Self {
+        ContractError::Paused {
+            msg: String::from(msg),
+        }
+    }
+
+    pub fn limit_exceeded(msg: &str) -> Self {
+        ContractError::LimitExceeded {
+            msg: String::from(msg),
+        }
+    }
+}
+
 impl From<&str> for ContractError {
     fn from(msg: &str) -> Self {
         ContractError::Std(StdError::generic_err(msg))
@@ -1,297 +1,3979 @@
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::convert::TryInto;
 
-use cosmwasm_std::{coins, Addr, BankMsg, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    coins, Addr, BankMsg, Deps, DepsMut, Env, Event, MessageInfo, Response, StdResult, Storage,
+    Uint128,
+};
 use provwasm_std::{
     burn_marker_supply, mint_marker_supply, withdraw_coins, ProvenanceQuerier, ProvenanceQuery,
 };
 
 use crate::{
+    accounting::{committed_shares, remaining_commitment},
     contract::ContractResponse,
-    error::contract_error,
-    msg::{AssetExchange, ExchangeDate, IssueAssetExchange},
-    state::{accepted_subscriptions_read, asset_exchange_storage, config_read},
+    error::{contract_error, ContractError},
+    msg::{
+        ActionKind, AssetExchange, CapitalCall, CapitalCallNotice, Distribution, ExchangeDate,
+        IssueAssetExchange,
+    },
+    state::{
+        accepted_subscriptions_read, asset_exchange_storage, asset_exchange_storage_read,
+        called_capital_storage, called_capital_storage_read, capital_call_notice_storage,
+        commitment_cap_storage_read, config, config_read, frozen_subscriptions_read,
+        lockup_storage, lockup_storage_read, lp_to_subscription_read, outstanding_distributions,
+        outstanding_distributions_read, processed_idempotency_keys,
+        processed_idempotency_keys_read, redeemed_capital_storage, redeemed_capital_storage_read,
+        redemption_cap_storage, redemption_cap_storage_read, rounding_residual_storage,
+        rounding_residual_storage_read, transfer_restriction_storage, RoundingPolicy,
+    },
+    sub_msg::{SubQueryMsg, SubState},
 };
 
+const MAX_REEMITTED_REDEMPTIONS: usize = 50;
+// dates this far out are almost certainly milliseconds or a typo rather than epoch seconds
+const MAX_EXCHANGE_DATE_HORIZON_SECONDS: u64 = 50 * 365 * 24 * 60 * 60;
+const MAX_PROCESSED_IDEMPOTENCY_KEYS: usize = 100;
+
+// records the key, returning true when the same batch was already processed
+fn is_replay(storage: &mut dyn Storage, scope: &str, key: &Option<String>) -> StdResult<bool> {
+    let key = match key {
+        Some(key) => format!("{}:{}", scope, key),
+        None => return Ok(false),
+    };
+
+    let mut processed = processed_idempotency_keys_read(storage)
+        .may_load()?
+        .unwrap_or_default();
+    if processed.contains(&key) {
+        return Ok(true);
+    }
+
+    // only recent keys are kept, which is enough to cover resubmitted transactions
+    processed.push(key);
+    if processed.len() > MAX_PROCESSED_IDEMPOTENCY_KEYS {
+        processed.remove(0);
+    }
+    processed_idempotency_keys(storage).save(&processed)?;
+
+    Ok(false)
+}
+
 pub fn try_issue_asset_exchanges(
     deps: DepsMut<ProvenanceQuery>,
+    env: Env,
     info: MessageInfo,
     asset_exchanges: Vec<IssueAssetExchange>,
+    idempotency_key: Option<String>,
 ) -> ContractResponse {
     let state = config_read(deps.storage).load()?;
     let accepted = accepted_subscriptions_read(deps.storage)
         .may_load()?
         .unwrap_or_default();
-    let mut storage = asset_exchange_storage(deps.storage);
 
     if info.sender != state.gp {
-        return contract_error("only gp can issue redemptions");
+        return Err(ContractError::unauthorized("only gp can issue redemptions"));
+    }
+
+    if is_replay(deps.storage, "issue", &idempotency_key)? {
+        return Ok(Response::new().add_attribute("replayed", "true"));
     }
 
+    let mut duplicates = 0;
     for issuance in asset_exchanges {
         if !accepted.contains(&issuance.subscription) {
-            return contract_error("subscription not accepted");
+            return Err(ContractError::subscription_not_found(&format!(
+                "subscription not accepted: {}",
+                issuance.subscription
+            )));
         }
 
-        let mut existing = storage
+        validate_installments(&issuance.exchange)?;
+        validate_exchange_date(&env, &issuance.exchange.date)?;
+        validate_exchange_expiry(&env, &issuance.exchange.date, issuance.exchange.expires_at)?;
+
+        let mut existing = asset_exchange_storage_read(deps.storage)
             .may_load(issuance.subscription.as_bytes())?
             .unwrap_or_default();
 
-        existing.push(issuance.exchange.clone());
+        // bulk imports can repeat a redemption, so identical ones are only recorded once
+        if issuance.exchange.investment.unwrap_or(0) < 0
+            && existing.iter().any(|e| e.matches(&issuance.exchange))
+        {
+            duplicates += 1;
+            continue;
+        }
+
+        adjust_called_capital(
+            deps.storage,
+            &issuance.subscription,
+            &issuance.exchange,
+            false,
+        )?;
+        existing.push(AssetExchange {
+            issued_at: Some(env.block.time.seconds()),
+            ..issuance.exchange
+        });
 
-        storage.save(issuance.subscription.as_bytes(), &existing)?;
+        asset_exchange_storage(deps.storage).save(issuance.subscription.as_bytes(), &existing)?;
     }
 
-    Ok(Response::default())
+    Ok(Response::new().add_attribute("skipped_duplicates", duplicates.to_string()))
 }
 
-pub fn try_cancel_asset_exchanges(
+// paid calls leave the queue, so the capital called is kept as a running total
+fn adjust_called_capital(
+    storage: &mut dyn Storage,
+    subscription: &Addr,
+    exchange: &AssetExchange,
+    cancelled: bool,
+) -> Result<(), ContractError> {
+    if !exchange.is_capital_call() {
+        return Ok(());
+    }
+
+    let capital = exchange.capital.unwrap_or(0).unsigned_abs();
+    let called = called_capital_storage_read(storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+    let called = if cancelled {
+        called.saturating_sub(capital)
+    } else {
+        called
+            .checked_add(capital)
+            .ok_or("called capital overflow")?
+    };
+    called_capital_storage(storage).save(subscription.as_bytes(), &called)?;
+
+    Ok(())
+}
+
+pub fn validate_exchange_date(env: &Env, date: &Option<ExchangeDate>) -> Result<(), ContractError> {
+    if let Some(date) = date {
+        let horizon = env
+            .block
+            .time
+            .seconds()
+            .saturating_add(MAX_EXCHANGE_DATE_HORIZON_SECONDS);
+        if date.epoch_seconds() > horizon {
+            return Err(ContractError::from(
+                "exchange date is too far in the future",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// an exchange has to be claimable for some window before it expires
+pub fn validate_exchange_expiry(
+    env: &Env,
+    date: &Option<ExchangeDate>,
+    expires_at: Option<u64>,
+) -> Result<(), ContractError> {
+    if let Some(expires_at) = expires_at {
+        let available = date
+            .as_ref()
+            .map(|date| date.epoch_seconds())
+            .unwrap_or_else(|| env.block.time.seconds());
+        if expires_at <= available.max(env.block.time.seconds()) {
+            return Err(ContractError::from(
+                "exchange must expire after it becomes available",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_installments(exchange: &AssetExchange) -> Result<(), ContractError> {
+    if exchange.installments.is_empty() {
+        return Ok(());
+    }
+
+    let investment = exchange.investment.unwrap_or(0);
+    if investment >= 0 || exchange.commitment_in_shares.is_some() {
+        return Err(ContractError::from(
+            "only redemptions can be paid in installments",
+        ));
+    }
+
+    let scheduled = exchange
+        .installments
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or("installment overflow")?;
+    if scheduled != investment.unsigned_abs() {
+        return Err(ContractError::from(
+            "installments must sum to the redeemed investment",
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn try_issue_redemptions_by_commitment_pct(
     deps: DepsMut<ProvenanceQuery>,
+    env: Env,
     info: MessageInfo,
-    cancellations: Vec<IssueAssetExchange>,
+    subscriptions: HashSet<Addr>,
+    pct_bps: u64,
 ) -> ContractResponse {
     let state = config_read(deps.storage).load()?;
-    let mut storage = asset_exchange_storage(deps.storage);
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
 
     if info.sender != state.gp {
-        return contract_error("only gp can cancel redemptions");
+        return Err(ContractError::unauthorized("only gp can issue redemptions"));
     }
 
-    for cancel in &cancellations {
-        let mut existing = storage
-            .may_load(cancel.subscription.as_bytes())?
-            .ok_or("no asset exchange found for subscription")?;
+    if pct_bps > 10_000 {
+        return contract_error("redemption percentage cannot exceed 10000 bps");
+    }
 
-        let index = existing
-            .iter()
-            .position(|e| &cancel.exchange == e)
-            .ok_or("no asset exchange found for subcription")?;
-        existing.remove(index);
+    for subscription in subscriptions {
+        if !accepted.contains(&subscription) {
+            return Err(ContractError::subscription_not_found(
+                "subscription not accepted",
+            ));
+        }
+
+        let mut existing = asset_exchange_storage_read(deps.storage)
+            .may_load(subscription.as_bytes())?
+            .unwrap_or_default();
+
+        // committed shares are those taken on at acceptance, not the live balance
+        let committed_shares = committed_shares(deps.as_ref(), &subscription)?;
+
+        // fractional shares are carried forward so repeated redemptions reconcile
+        let residual = rounding_residual_storage_read(deps.storage)
+            .may_load(subscription.as_bytes())?
+            .unwrap_or_default();
+        let scaled = i128::from(committed_shares) * i128::from(pct_bps) + i128::from(residual);
+        let rounded = state
+            .redemption_rounding
+            .unwrap_or(RoundingPolicy::Floor)
+            .round_bps(scaled);
+        rounding_residual_storage(deps.storage).save(
+            subscription.as_bytes(),
+            &(scaled - rounded * 10_000).try_into()?,
+        )?;
+
+        let redeemed_shares: u64 = rounded.try_into()?;
+        if redeemed_shares == 0 {
+            continue;
+        }
+        let redeemed_capital = state.shares_to_capital(redeemed_shares)?;
+
+        existing.push(AssetExchange {
+            investment: Some(-redeemed_shares.try_into()?),
+            commitment_in_shares: None,
+            capital: Some(redeemed_capital.try_into()?),
+            date: None,
+            issued_at: Some(env.block.time.seconds()),
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        });
 
-        storage.save(cancel.subscription.as_bytes(), &existing)?;
+        asset_exchange_storage(deps.storage).save(subscription.as_bytes(), &existing)?;
     }
 
     Ok(Response::default())
 }
 
-pub fn try_complete_asset_exchange(
+pub fn try_issue_proportional_redemptions(
     deps: DepsMut<ProvenanceQuery>,
     env: Env,
     info: MessageInfo,
-    exchanges: Vec<AssetExchange>,
-    to: Option<Addr>,
-    memo: Option<String>,
+    subscriptions: HashSet<Addr>,
+    total_capital: u64,
 ) -> ContractResponse {
     let state = config_read(deps.storage).load()?;
-    let mut storage = asset_exchange_storage(deps.storage);
-
-    let mut existing = storage
-        .may_load(info.sender.as_bytes())?
-        .ok_or("no asset exchange found for subscription")?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
 
-    for exchange in &exchanges {
-        let index = existing
-            .iter()
-            .position(|e| exchange == e)
-            .ok_or("no asset exchange found for subcription")?;
-        existing.remove(index);
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized("only gp can issue redemptions"));
+    }
 
-        storage.save(info.sender.as_bytes(), &existing)?;
+    let mut subscriptions: Vec<Addr> = subscriptions.into_iter().collect();
+    subscriptions.sort();
 
-        if let Some(date) = &exchange.date {
-            match date {
-                ExchangeDate::Due(epoch_seconds) => {
-                    if epoch_seconds < &env.block.time.seconds() {
-                        return contract_error("exchange past due");
-                    }
-                }
-                ExchangeDate::Available(epoch_seconds) => {
-                    if epoch_seconds > &env.block.time.seconds() {
-                        return contract_error("exchange not yet available");
-                    }
-                }
-            }
+    let mut committed = vec![];
+    for subscription in subscriptions {
+        if !accepted.contains(&subscription) {
+            return Err(ContractError::subscription_not_found(
+                "subscription not accepted",
+            ));
         }
+
+        let existing = asset_exchange_storage_read(deps.storage)
+            .may_load(subscription.as_bytes())?
+            .unwrap_or_default();
+        let committed_shares = committed_shares(deps.as_ref(), &subscription)?;
+        committed.push((subscription, existing, committed_shares));
     }
 
-    let mut response = Response::new();
+    let mut storage = asset_exchange_storage(deps.storage);
 
-    let total_investment: i64 = exchanges.iter().filter_map(|e| e.investment).sum();
-    let abs_investment = total_investment.unsigned_abs();
-    match total_investment.cmp(&0) {
-        Ordering::Less => {
-            let investment_marker = ProvenanceQuerier::new(&deps.querier)
-                .get_marker_by_denom(state.investment_denom.clone())?;
-            let deposit_investment = BankMsg::Send {
-                to_address: investment_marker.address.into_string(),
-                amount: coins(abs_investment.into(), state.investment_denom.clone()),
-            };
-            let burn_investment =
-                burn_marker_supply(abs_investment.into(), state.investment_denom.clone())?;
+    let total_committed: i128 = committed
+        .iter()
+        .map(|(_, _, shares)| i128::from(*shares))
+        .sum();
+    if total_committed == 0 {
+        return contract_error("no committed shares to redeem against");
+    }
 
-            response = response
-                .add_message(deposit_investment)
-                .add_message(burn_investment);
+    // each share of capital is floored to a whole lot, leaving the remainder undistributed
+    let mut distributed: u64 = 0;
+    for (subscription, mut existing, committed_shares) in committed {
+        let capital: u64 = (i128::from(total_capital)
+            .checked_mul(i128::from(committed_shares))
+            .ok_or("proportional redemption overflow")?
+            / total_committed)
+            .try_into()?;
+        let redeemed_shares = state.capital_to_shares(capital)?;
+        if redeemed_shares == 0 {
+            continue;
         }
-        Ordering::Greater => {
-            let mint_investment =
-                mint_marker_supply(abs_investment.into(), state.investment_denom.clone())?;
-            let withdraw_investment = withdraw_coins(
-                state.investment_denom.clone(),
-                abs_investment.into(),
-                state.investment_denom.clone(),
-                info.sender.clone(),
-            )?;
+        let redeemed_capital = state.shares_to_capital(redeemed_shares)?;
 
-            response = response
-                .add_message(mint_investment)
-                .add_message(withdraw_investment);
-        }
-        _ => {}
+        existing.push(AssetExchange {
+            investment: Some(-redeemed_shares.try_into()?),
+            commitment_in_shares: None,
+            capital: Some(redeemed_capital.try_into()?),
+            date: None,
+            issued_at: Some(env.block.time.seconds()),
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        });
+        storage.save(subscription.as_bytes(), &existing)?;
+
+        distributed = distributed
+            .checked_add(redeemed_capital)
+            .ok_or("proportional redemption overflow")?;
     }
 
-    let total_commitment: i64 = exchanges
-        .iter()
-        .filter_map(|e| e.commitment_in_shares)
-        .sum();
-    let abs_commitment = total_commitment.unsigned_abs();
-    match total_commitment.cmp(&0) {
-        Ordering::Less => {
-            let deposit_commitment = BankMsg::Send {
-                to_address: ProvenanceQuerier::new(&deps.querier)
-                    .get_marker_by_denom(state.commitment_denom.clone())?
-                    .address
-                    .into_string(),
-                amount: coins(abs_commitment.into(), state.commitment_denom.clone()),
-            };
-            let burn_commitment =
-                burn_marker_supply(abs_commitment.into(), state.commitment_denom)?;
+    Ok(Response::new().add_attribute(
+        "leftover_capital",
+        (total_capital - distributed).to_string(),
+    ))
+}
 
-            response = response
-                .add_message(deposit_commitment)
-                .add_message(burn_commitment);
+pub fn try_issue_capital_calls(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    calls: Vec<CapitalCall>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can issue capital calls",
+        ));
+    }
+
+    for call in calls {
+        if !accepted.contains(&call.subscription) {
+            return Err(ContractError::subscription_not_found(
+                "subscription not accepted",
+            ));
         }
-        Ordering::Greater => {
-            let mint_commitment =
-                mint_marker_supply(abs_commitment.into(), state.commitment_denom.clone())?;
-            let withdraw_commitment = withdraw_coins(
-                state.commitment_denom.clone(),
-                abs_commitment.into(),
-                state.commitment_denom,
-                info.sender.clone(),
-            )?;
 
-            response = response
-                .add_message(mint_commitment)
-                .add_message(withdraw_commitment);
+        if call.amount == 0 || state.not_evenly_divisble(call.amount) {
+            return Err(ContractError::not_divisible(
+                "capital call must be evenly divisble by capital per share",
+            ));
         }
-        _ => {}
-    }
 
-    let total_capital: i64 = exchanges.iter().filter_map(|e| e.capital).sum();
-    let abs_capital = total_capital.unsigned_abs();
-    if total_capital > 0 {
-        let send_capital = BankMsg::Send {
-            to_address: to.unwrap_or(info.sender).into_string(),
-            amount: coins(abs_capital.into(), state.capital_denom),
-        };
+        // completed exchanges leave the queue, so held commitment comes from the marker balance
+        let shares = state.capital_to_shares(call.amount)?;
+        if shares > remaining_commitment(deps.as_ref(), &call.subscription)? {
+            return Err(ContractError::limit_exceeded(
+                "capital call exceeds remaining committed shares",
+            ));
+        }
+        let shares: i64 = shares.try_into()?;
 
-        response = response.add_message(send_capital);
+        let mut existing = asset_exchange_storage_read(deps.storage)
+            .may_load(call.subscription.as_bytes())?
+            .unwrap_or_default();
+
+        // paid calls leave the queue, so the cap is checked against everything called so far
+        let called = called_capital_storage_read(deps.storage)
+            .may_load(call.subscription.as_bytes())?
+            .unwrap_or_default()
+            .checked_add(call.amount)
+            .ok_or("called capital overflow")?;
+        let cap = commitment_cap_storage_read(deps.storage)
+            .may_load(call.subscription.as_bytes())?
+            .or(state.max_commitment);
+        if let Some(cap) = cap {
+            if called > cap {
+                return Err(ContractError::limit_exceeded(
+                    "capital call exceeds commitment cap",
+                ));
+            }
+        }
+
+        // capital is owed by the sub, so it is negative like other capital calls
+        existing.push(AssetExchange {
+            investment: Some(shares),
+            commitment_in_shares: Some(-shares),
+            capital: Some(-call.amount.try_into()?),
+            date: Some(ExchangeDate::Available(env.block.time.seconds())),
+            issued_at: Some(env.block.time.seconds()),
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        });
+
+        asset_exchange_storage(deps.storage).save(call.subscription.as_bytes(), &existing)?;
+        called_capital_storage(deps.storage).save(call.subscription.as_bytes(), &called)?;
     }
 
-    Ok(match memo {
-        Some(memo) => response.add_attribute(String::from("memo"), memo),
-        None => response,
-    })
+    Ok(Response::default())
 }
 
-#[cfg(test)]
-pub mod tests {
-    use super::*;
-    use crate::contract::execute;
-    use crate::contract::tests::default_deps;
-    use crate::mock::burn_args;
-    use crate::mock::load_markers;
-    use crate::mock::msg_at_index;
-    use crate::mock::send_args;
-    use crate::msg::HandleMsg;
-    use crate::msg::IssueAssetExchange;
-    use crate::state::tests::asset_exchange_storage_read;
-    use crate::state::tests::set_accepted;
-    use cosmwasm_std::from_binary;
-    use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::to_binary;
-    use cosmwasm_std::Addr;
-    use cosmwasm_std::Timestamp;
+pub fn try_reduce_commitment(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    subscription: Addr,
+    reduce_shares: u64,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
 
-    #[test]
-    fn size() {
-        let exchange = AssetExchange {
-            investment: Some(-1_000),
-            commitment_in_shares: None,
-            capital: Some(1_000),
-            date: Some(ExchangeDate::Available(0)),
-        };
-        let as_bytes = to_binary(&exchange).unwrap();
-        println!("{}", std::str::from_utf8(as_bytes.as_slice()).unwrap());
-        assert_eq!(41, as_bytes.len());
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can reduce commitments",
+        ));
+    }
 
-        println!("{:?}", from_binary::<AssetExchange>(&as_bytes).unwrap());
+    if !accepted.contains(&subscription) {
+        return Err(ContractError::subscription_not_found(
+            "subscription not accepted",
+        ));
     }
 
-    #[test]
-    fn issue_asset_exchange_for_capital_call() {
-        let mut deps = default_deps(None);
-        set_accepted(&mut deps.storage, vec!["sub_1"]);
-        {
-            asset_exchange_storage(&mut deps.storage)
-                .save(
-                    Addr::unchecked("sub_1").as_bytes(),
-                    &vec![AssetExchange {
-                        investment: None,
-                        commitment_in_shares: Some(1_000),
-                        capital: None,
-                        date: None,
-                    }],
-                )
-                .unwrap();
-        }
+    if reduce_shares == 0 {
+        return contract_error("commitment reduction must be greater than zero");
+    }
 
-        execute(
-            deps.as_mut(),
-            mock_env(),
-            mock_info("gp", &vec![]),
-            HandleMsg::IssueAssetExchanges {
+    // commitment already held by the sub counts alongside anything still pending
+    if reduce_shares > remaining_commitment(deps.as_ref(), &subscription)? {
+        return Err(ContractError::limit_exceeded(
+            "commitment reduction exceeds net committed shares",
+        ));
+    }
+
+    let mut existing = asset_exchange_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+
+    existing.push(AssetExchange {
+        investment: None,
+        commitment_in_shares: Some(-reduce_shares.try_into()?),
+        capital: None,
+        date: None,
+        issued_at: Some(env.block.time.seconds()),
+        installments: vec![],
+        gp_note: None,
+        memo: None,
+        expires_at: None,
+    });
+
+    asset_exchange_storage(deps.storage).save(subscription.as_bytes(), &existing)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_emit_statement(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    subscription: Addr,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if !accepted.contains(&subscription) {
+        return Err(ContractError::subscription_not_found(
+            "subscription not accepted",
+        ));
+    }
+
+    if info.sender != state.gp {
+        let sub_state: SubState = deps
+            .querier
+            .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetState {})?;
+
+        if sub_state.raise != env.contract.address || info.sender != sub_state.lp {
+            return Err(ContractError::unauthorized(
+                "only gp or subscription lp can emit statement",
+            ));
+        }
+    }
+
+    let exchanges = asset_exchange_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+
+    // settled exchanges leave the queue, so positions come from balances and running totals
+    let committed_shares = committed_shares(deps.as_ref(), &subscription)?;
+    let called_capital = called_capital_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+    let outstanding_redemptions: u64 = exchanges
+        .iter()
+        .filter(|e| e.investment.unwrap_or(0) < 0)
+        .filter_map(|e| e.capital)
+        .map(|capital| capital.unsigned_abs())
+        .sum();
+    let redeemed_capital = redeemed_capital_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+
+    Ok(Response::new()
+        .add_attribute("subscription", subscription)
+        .add_attribute("committed_shares", committed_shares.to_string())
+        .add_attribute("called_capital", called_capital.to_string())
+        .add_attribute("redeemed_capital", redeemed_capital.to_string())
+        .add_attribute(
+            "outstanding_redemptions",
+            outstanding_redemptions.to_string(),
+        ))
+}
+
+pub fn try_reemit_redemption_events(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can reemit redemption events",
+        ));
+    }
+
+    let mut subscriptions: Vec<Addr> = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    subscriptions.sort();
+
+    let mut events = vec![];
+    for subscription in subscriptions {
+        for redemption in asset_exchange_storage_read(deps.storage)
+            .may_load(subscription.as_bytes())?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| e.investment.unwrap_or(0) < 0)
+        {
+            if events.len() == MAX_REEMITTED_REDEMPTIONS {
+                return Ok(Response::new()
+                    .add_events(events)
+                    .add_attribute("truncated", "true"));
+            }
+
+            events.push(
+                Event::new("redemption")
+                    .add_attribute("subscription", subscription.clone())
+                    .add_attribute(
+                        "asset",
+                        redemption
+                            .investment
+                            .unwrap_or(0)
+                            .unsigned_abs()
+                            .to_string(),
+                    )
+                    .add_attribute(
+                        "capital",
+                        redemption.capital.unwrap_or(0).unsigned_abs().to_string(),
+                    ),
+            );
+        }
+    }
+
+    Ok(Response::new().add_events(events))
+}
+
+pub fn try_cancel_redemptions_older_than(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    issued_before_epoch_seconds: u64,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut storage = asset_exchange_storage(deps.storage);
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can cancel redemptions",
+        ));
+    }
+
+    let mut cancelled = 0;
+    for subscription in accepted {
+        let existing = match storage.may_load(subscription.as_bytes())? {
+            Some(existing) => existing,
+            None => continue,
+        };
+
+        // exchanges issued before issue dates were recorded are left alone
+        let remaining: Vec<AssetExchange> = existing
+            .iter()
+            .filter(|e| {
+                let stale = matches!(
+                    e.issued_at,
+                    Some(issued_at) if issued_at < issued_before_epoch_seconds
+                );
+                !(e.investment.unwrap_or(0) < 0 && stale)
+            })
+            .cloned()
+            .collect();
+
+        if remaining.len() < existing.len() {
+            cancelled += existing.len() - remaining.len();
+            storage.save(subscription.as_bytes(), &remaining)?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("cancelled", cancelled.to_string()))
+}
+
+pub fn try_sweep_expired_redemptions(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut storage = asset_exchange_storage(deps.storage);
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can sweep expired redemptions",
+        ));
+    }
+
+    let now = env.block.time.seconds();
+    let mut swept = 0;
+    for subscription in accepted {
+        let existing = match storage.may_load(subscription.as_bytes())? {
+            Some(existing) => existing,
+            None => continue,
+        };
+
+        let remaining: Vec<AssetExchange> = existing
+            .iter()
+            .filter(|e| !(e.investment.unwrap_or(0) < 0 && e.expired_at(now)))
+            .cloned()
+            .collect();
+
+        if remaining.len() < existing.len() {
+            swept += existing.len() - remaining.len();
+            storage.save(subscription.as_bytes(), &remaining)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "sweep_expired_redemptions")
+        .add_attribute("swept", swept.to_string()))
+}
+
+pub fn try_emergency_halt(deps: DepsMut<ProvenanceQuery>, info: MessageInfo) -> ContractResponse {
+    let mut state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if info.sender != state.gp && info.sender != state.recovery_admin {
+        return Err(ContractError::unauthorized(
+            "only gp or admin can halt raise",
+        ));
+    }
+
+    let mut storage = asset_exchange_storage(deps.storage);
+    let mut cancelled = 0;
+    let mut cancelled_capital: i64 = 0;
+    let mut affected_subscriptions = 0;
+    for subscription in accepted {
+        let existing = match storage.may_load(subscription.as_bytes())? {
+            Some(existing) => existing,
+            None => continue,
+        };
+
+        let (redemptions, remaining): (Vec<AssetExchange>, Vec<AssetExchange>) = existing
+            .into_iter()
+            .partition(|e| e.investment.unwrap_or(0) < 0);
+
+        if !redemptions.is_empty() {
+            cancelled += redemptions.len();
+            cancelled_capital += redemptions
+                .iter()
+                .map(|e| e.capital.unwrap_or(0))
+                .sum::<i64>();
+            affected_subscriptions += 1;
+            storage.save(subscription.as_bytes(), &remaining)?;
+        }
+    }
+
+    // the gp halts at the raise level so it can lift the pause itself with SetPaused
+    if info.sender == state.recovery_admin {
+        state.paused = true;
+    } else {
+        state.paused_actions = ActionKind::all();
+    }
+    config(deps.storage).save(&state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "emergency_halt")
+        .add_attribute("cancelled", cancelled.to_string())
+        .add_attribute("cancelled_capital", cancelled_capital.to_string())
+        .add_attribute("subscriptions", affected_subscriptions.to_string()))
+}
+
+pub fn try_issue_distributions(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    distributions: Vec<Distribution>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut storage = outstanding_distributions(deps.storage);
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can issue distributions",
+        ));
+    }
+
+    let issued = distributions.len();
+    for distribution in distributions {
+        if !accepted.contains(&distribution.subscription) {
+            return Err(ContractError::subscription_not_found(&format!(
+                "subscription not accepted: {}",
+                distribution.subscription
+            )));
+        }
+
+        if distribution.capital == 0 {
+            return contract_error("distribution capital must be greater than zero");
+        }
+
+        let mut existing = storage
+            .may_load(distribution.subscription.as_bytes())?
+            .unwrap_or_default();
+        existing.push(distribution.clone());
+        storage.save(distribution.subscription.as_bytes(), &existing)?;
+    }
+
+    Ok(Response::new().add_attribute("issued", issued.to_string()))
+}
+
+pub fn try_claim_distribution(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+
+    if frozen_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+        .contains(&info.sender)
+    {
+        return Err(ContractError::frozen("subscription is frozen"));
+    }
+
+    let existing = outstanding_distributions_read(deps.storage)
+        .may_load(info.sender.as_bytes())?
+        .ok_or_else(|| {
+            ContractError::distribution_not_found("no distribution found for subscription")
+        })?;
+
+    // distributions pay capital only, so investment shares stay outstanding
+    let (available, remaining): (Vec<Distribution>, Vec<Distribution>) = existing
+        .into_iter()
+        .partition(|d| d.available_epoch_seconds <= env.block.time.seconds());
+    if available.is_empty() {
+        return contract_error("distribution not yet available");
+    }
+
+    outstanding_distributions(deps.storage).save(info.sender.as_bytes(), &remaining)?;
+
+    let capital = available
+        .iter()
+        .try_fold(0u64, |total, d| total.checked_add(d.capital))
+        .ok_or("distribution overflow")?;
+
+    // a short balance would otherwise only surface as a failed bank send
+    let capital_balance = deps
+        .querier
+        .query_balance(env.contract.address.as_str(), state.capital_denom.clone())?;
+    if capital_balance.amount < Uint128::from(capital) {
+        return Err(ContractError::insufficient_funds(
+            "insufficient capital in raise to fund distribution",
+        ));
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(capital.into(), state.capital_denom),
+        })
+        .add_attribute("action", "claim_distribution")
+        .add_attribute("subscription", info.sender)
+        .add_attribute("capital", capital.to_string()))
+}
+
+pub fn try_set_availability_for_subscriptions(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    subscriptions: HashSet<Addr>,
+    available_epoch_seconds: u64,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut storage = asset_exchange_storage(deps.storage);
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can set redemption availability",
+        ));
+    }
+
+    validate_exchange_date(
+        &env,
+        &Some(ExchangeDate::Available(available_epoch_seconds)),
+    )?;
+
+    let mut updated = 0;
+    for subscription in subscriptions {
+        if !accepted.contains(&subscription) {
+            return Err(ContractError::subscription_not_found(
+                "subscription not accepted",
+            ));
+        }
+
+        let mut existing = match storage.may_load(subscription.as_bytes())? {
+            Some(existing) => existing,
+            None => continue,
+        };
+
+        for exchange in existing.iter_mut() {
+            if exchange.investment.unwrap_or(0) < 0 {
+                exchange.date = Some(ExchangeDate::Available(available_epoch_seconds));
+                updated += 1;
+            }
+        }
+
+        storage.save(subscription.as_bytes(), &existing)?;
+    }
+
+    Ok(Response::new().add_attribute("updated", updated.to_string()))
+}
+
+// per subscription terms are set by the gp, on accepted subscriptions, while config is unlocked
+fn ensure_subscription_terms_editable(
+    deps: Deps<ProvenanceQuery>,
+    info: &MessageInfo,
+    subscription: &Addr,
+    unauthorized: &str,
+) -> Result<(), ContractError> {
+    let state = config_read(deps.storage).load()?;
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(unauthorized));
+    }
+
+    state.ensure_config_unlocked()?;
+
+    if !accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+        .contains(subscription)
+    {
+        return Err(ContractError::subscription_not_found(
+            "subscription not accepted",
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn try_set_lockup(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    subscription: Addr,
+    lockup_end_epoch_seconds: u64,
+) -> ContractResponse {
+    ensure_subscription_terms_editable(
+        deps.as_ref(),
+        &info,
+        &subscription,
+        "only gp can set lockups",
+    )?;
+
+    lockup_storage(deps.storage).save(subscription.as_bytes(), &lockup_end_epoch_seconds)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_redemption_cap(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    subscription: Addr,
+    max_redemption_capital: Option<u64>,
+) -> ContractResponse {
+    ensure_subscription_terms_editable(
+        deps.as_ref(),
+        &info,
+        &subscription,
+        "only gp can set redemption caps",
+    )?;
+
+    match max_redemption_capital {
+        Some(cap) => redemption_cap_storage(deps.storage).save(subscription.as_bytes(), &cap)?,
+        None => redemption_cap_storage(deps.storage).remove(subscription.as_bytes()),
+    }
+
+    Ok(Response::default())
+}
+
+pub fn try_set_transfer_restriction(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    subscription: Addr,
+    restricted_until_epoch_seconds: u64,
+) -> ContractResponse {
+    ensure_subscription_terms_editable(
+        deps.as_ref(),
+        &info,
+        &subscription,
+        "only gp can set transfer restrictions",
+    )?;
+
+    transfer_restriction_storage(deps.storage)
+        .save(subscription.as_bytes(), &restricted_until_epoch_seconds)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_redemption_note(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    subscription: Addr,
+    asset: u64,
+    capital: u64,
+    gp_note: Option<String>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can set redemption notes",
+        ));
+    }
+
+    let mut existing = asset_exchange_storage_read(deps.storage)
+        .may_load(subscription.as_bytes())?
+        .ok_or_else(|| {
+            ContractError::asset_exchange_not_found("no asset exchange found for subscription")
+        })?;
+
+    let investment: i64 = asset.try_into()?;
+    let capital: i64 = capital.try_into()?;
+    let redemption = existing
+        .iter_mut()
+        .find(|e| {
+            e.investment == Some(-investment)
+                && e.commitment_in_shares.is_none()
+                && e.capital == Some(capital)
+        })
+        .ok_or_else(|| {
+            ContractError::redemption_not_found("no redemption found for subscription")
+        })?;
+    redemption.gp_note = gp_note;
+
+    asset_exchange_storage(deps.storage).save(subscription.as_bytes(), &existing)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_notice_capital_call(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    subscriptions: HashSet<Addr>,
+    amount: u64,
+    effective_epoch_seconds: u64,
+    memo: Option<String>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut storage = capital_call_notice_storage(deps.storage);
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can notice capital calls",
+        ));
+    }
+
+    // notices are informational only, no asset exchange is issued until the call is formalized
+    let mut subscriptions: Vec<Addr> = subscriptions.into_iter().collect();
+    subscriptions.sort();
+
+    let mut response = Response::new()
+        .add_attribute("amount", amount.to_string())
+        .add_attribute(
+            "effective_epoch_seconds",
+            effective_epoch_seconds.to_string(),
+        );
+    for subscription in subscriptions {
+        if !accepted.contains(&subscription) {
+            return Err(ContractError::subscription_not_found(
+                "subscription not accepted",
+            ));
+        }
+
+        let mut notices = storage
+            .may_load(subscription.as_bytes())?
+            .unwrap_or_default();
+        notices.push(CapitalCallNotice {
+            amount,
+            effective_epoch_seconds,
+            memo: memo.clone(),
+        });
+        storage.save(subscription.as_bytes(), &notices)?;
+
+        response = response.add_attribute("capital_call_notice", subscription);
+    }
+
+    Ok(match memo {
+        Some(memo) => response.add_attribute(String::from("memo"), memo),
+        None => response,
+    })
+}
+
+pub fn try_cancel_asset_exchanges(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    cancellations: Vec<IssueAssetExchange>,
+    idempotency_key: Option<String>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can cancel redemptions",
+        ));
+    }
+
+    if is_replay(deps.storage, "cancel", &idempotency_key)? {
+        return Ok(Response::new().add_attribute("replayed", "true"));
+    }
+
+    for cancel in &cancellations {
+        let mut existing = asset_exchange_storage_read(deps.storage)
+            .may_load(cancel.subscription.as_bytes())?
+            .ok_or_else(|| {
+                ContractError::asset_exchange_not_found("no asset exchange found for subscription")
+            })?;
+
+        let index = existing
+            .iter()
+            .position(|e| e.matches(&cancel.exchange))
+            .ok_or_else(|| {
+                ContractError::asset_exchange_not_found("no asset exchange found for subscription")
+            })?;
+        let cancelled = existing.remove(index);
+
+        asset_exchange_storage(deps.storage).save(cancel.subscription.as_bytes(), &existing)?;
+        adjust_called_capital(deps.storage, &cancel.subscription, &cancelled, true)?;
+    }
+
+    Ok(Response::default())
+}
+
+pub fn try_complete_asset_exchange(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    exchanges: Vec<AssetExchange>,
+    to: Option<Addr>,
+    memo: Option<String>,
+    subscription: Option<Addr>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+
+    // an lp may complete exchanges on behalf of its subscription if the raise allows it
+    let subscription = match subscription {
+        Some(subscription) if subscription != info.sender => {
+            if !state.allow_lp_claims {
+                return Err(ContractError::unauthorized(
+                    "only the subscription can complete its asset exchanges",
+                ));
+            }
+
+            let sub_state: SubState = deps
+                .querier
+                .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetState {})?;
+
+            if sub_state.raise != env.contract.address {
+                return Err(ContractError::subscription_not_found(
+                    "subscription belongs to a different raise",
+                ));
+            }
+
+            if info.sender != sub_state.lp {
+                return Err(ContractError::unauthorized(
+                    "only the subscription or its lp can complete its asset exchanges",
+                ));
+            }
+
+            subscription
+        }
+        _ => info.sender,
+    };
+
+    if frozen_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default()
+        .contains(&subscription)
+    {
+        return Err(ContractError::frozen("subscription is frozen"));
+    }
+
+    if exchanges.iter().any(|e| e.investment.unwrap_or(0) < 0) {
+        if state.redemptions_paused {
+            return Err(ContractError::paused("redemptions are paused"));
+        }
+
+        if let Some(lockup_end) =
+            lockup_storage_read(deps.storage).may_load(subscription.as_bytes())?
+        {
+            if lockup_end > env.block.time.seconds() {
+                return contract_error("subscription is locked up");
+            }
+        }
+
+        if state.restrict_redemption_destination {
+            if let Some(to) = &to {
+                let owned_by_destination = lp_to_subscription_read(deps.storage)
+                    .may_load(to.as_bytes())?
+                    .unwrap_or_default()
+                    .contains(&subscription);
+
+                if *to != subscription && !owned_by_destination {
+                    let sub_state: SubState = deps
+                        .querier
+                        .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetState {})?;
+
+                    if sub_state.lp != *to {
+                        return contract_error(
+                            "redemption destination must be the subscription or its lp",
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut storage = asset_exchange_storage(deps.storage);
+
+    let mut existing = storage.may_load(subscription.as_bytes())?.ok_or_else(|| {
+        ContractError::asset_exchange_not_found("no asset exchange found for subscription")
+    })?;
+
+    // redemptions paid in installments only settle their matured portion
+    let mut settled = vec![];
+    let mut issued_memos = vec![];
+    for exchange in &exchanges {
+        let index = existing
+            .iter()
+            .position(|e| e.matches(exchange))
+            .ok_or_else(|| {
+                ContractError::asset_exchange_not_found("no asset exchange found for subscription")
+            })?;
+        let stored = existing.remove(index);
+        if stored.investment.unwrap_or(0) < 0 && stored.expired_at(env.block.time.seconds()) {
+            return contract_error("redemption expired");
+        }
+        if let Some(memo) = &stored.memo {
+            issued_memos.push(memo.clone());
+        }
+
+        if stored.installments.is_empty() {
+            settled.push(exchange.clone());
+        } else {
+            let (matured, remaining): (Vec<_>, Vec<_>) = stored
+                .installments
+                .into_iter()
+                .partition(|(available, _)| *available <= env.block.time.seconds());
+            if matured.is_empty() {
+                return contract_error("no installment available");
+            }
+
+            let total_asset = stored.investment.unwrap_or(0).unsigned_abs();
+            let total_capital = stored.capital.unwrap_or(0);
+            let (asset, capital) = if remaining.is_empty() {
+                (total_asset, total_capital)
+            } else {
+                let asset: u64 = matured.iter().map(|(_, amount)| amount).sum();
+                let capital: i64 = (i128::from(total_capital) * i128::from(asset)
+                    / i128::from(total_asset))
+                .try_into()?;
+                (asset, capital)
+            };
+
+            settled.push(AssetExchange {
+                investment: Some(-asset.try_into()?),
+                commitment_in_shares: None,
+                capital: Some(capital),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            });
+
+            if !remaining.is_empty() {
+                existing.insert(
+                    index,
+                    AssetExchange {
+                        investment: Some(-(total_asset - asset).try_into()?),
+                        capital: Some(total_capital - capital),
+                        installments: remaining,
+                        ..stored
+                    },
+                );
+            }
+        }
+
+        storage.save(subscription.as_bytes(), &existing)?;
+
+        if let Some(date) = &exchange.date {
+            match date {
+                ExchangeDate::Due(epoch_seconds) => {
+                    if epoch_seconds < &env.block.time.seconds() {
+                        return contract_error("exchange past due");
+                    }
+                }
+                ExchangeDate::Available(epoch_seconds) => {
+                    if epoch_seconds > &env.block.time.seconds() {
+                        return contract_error("exchange not yet available");
+                    }
+                }
+            }
+        }
+    }
+
+    let mut response = Response::new();
+
+    let total_investment: i64 = settled.iter().filter_map(|e| e.investment).sum();
+    let abs_investment = total_investment.unsigned_abs();
+
+    // redeemed investment must be the only coin sent so nothing else is stranded in the raise
+    if total_investment < 0 {
+        match info.funds.as_slice() {
+            [coin]
+                if coin.denom == state.investment_denom
+                    && coin.amount.u128() == u128::from(abs_investment) => {}
+            _ => {
+                return Err(ContractError::insufficient_funds(&format!(
+                    "funds must be exactly {}{}",
+                    abs_investment, state.investment_denom
+                )))
+            }
+        }
+    }
+    match total_investment.cmp(&0) {
+        Ordering::Less => {
+            let investment_marker = ProvenanceQuerier::new(&deps.querier)
+                .get_marker_by_denom(state.investment_denom.clone())?;
+            let deposit_investment = BankMsg::Send {
+                to_address: investment_marker.address.into_string(),
+                amount: coins(abs_investment.into(), state.investment_denom.clone()),
+            };
+            let burn_investment =
+                burn_marker_supply(abs_investment.into(), state.investment_denom.clone())?;
+
+            response = response
+                .add_message(deposit_investment)
+                .add_message(burn_investment);
+        }
+        Ordering::Greater => {
+            let mint_investment =
+                mint_marker_supply(abs_investment.into(), state.investment_denom.clone())?;
+            let withdraw_investment = withdraw_coins(
+                state.investment_denom.clone(),
+                abs_investment.into(),
+                state.investment_denom.clone(),
+                subscription.clone(),
+            )?;
+
+            response = response
+                .add_message(mint_investment)
+                .add_message(withdraw_investment);
+        }
+        _ => {}
+    }
+
+    let total_commitment: i64 = settled.iter().filter_map(|e| e.commitment_in_shares).sum();
+    let abs_commitment = total_commitment.unsigned_abs();
+    match total_commitment.cmp(&0) {
+        Ordering::Less => {
+            let deposit_commitment = BankMsg::Send {
+                to_address: ProvenanceQuerier::new(&deps.querier)
+                    .get_marker_by_denom(state.commitment_denom.clone())?
+                    .address
+                    .into_string(),
+                amount: coins(abs_commitment.into(), state.commitment_denom.clone()),
+            };
+            let burn_commitment =
+                burn_marker_supply(abs_commitment.into(), state.commitment_denom)?;
+
+            response = response
+                .add_message(deposit_commitment)
+                .add_message(burn_commitment);
+        }
+        Ordering::Greater => {
+            let mint_commitment =
+                mint_marker_supply(abs_commitment.into(), state.commitment_denom.clone())?;
+            let withdraw_commitment = withdraw_coins(
+                state.commitment_denom.clone(),
+                abs_commitment.into(),
+                state.commitment_denom,
+                subscription.clone(),
+            )?;
+
+            response = response
+                .add_message(mint_commitment)
+                .add_message(withdraw_commitment);
+        }
+        _ => {}
+    }
+
+    let total_capital: i64 = settled.iter().filter_map(|e| e.capital).sum();
+    let abs_capital = total_capital.unsigned_abs();
+    if total_capital > 0 {
+        if total_investment < 0 {
+            let redeemed = redeemed_capital_storage(deps.storage).update(
+                subscription.as_bytes(),
+                |redeemed| -> Result<u64, ContractError> {
+                    redeemed
+                        .unwrap_or_default()
+                        .checked_add(abs_capital)
+                        .ok_or_else(|| ContractError::from("redeemed capital overflow"))
+                },
+            )?;
+
+            if let Some(cap) =
+                redemption_cap_storage_read(deps.storage).may_load(subscription.as_bytes())?
+            {
+                if redeemed > cap {
+                    return Err(ContractError::limit_exceeded(
+                        "redemption exceeds subscription redemption cap",
+                    ));
+                }
+            }
+        }
+
+        // a short balance would otherwise only surface as a failed bank send
+        let capital_balance = deps
+            .querier
+            .query_balance(env.contract.address.as_str(), state.capital_denom.clone())?;
+        if capital_balance.amount < Uint128::from(abs_capital) {
+            return Err(ContractError::insufficient_funds(
+                "insufficient capital in raise to fund redemption",
+            ));
+        }
+
+        let send_capital = BankMsg::Send {
+            to_address: to.unwrap_or(subscription).into_string(),
+            amount: coins(abs_capital.into(), state.capital_denom),
+        };
+
+        response = response.add_message(send_capital);
+    }
+
+    for issued_memo in issued_memos {
+        response = response.add_attribute("issued_memo", issued_memo);
+    }
+
+    Ok(match memo {
+        Some(memo) => response.add_attribute(String::from("memo"), memo),
+        None => response,
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::contract::tests::default_deps;
+    use crate::mock::burn_args;
+    use crate::mock::load_markers;
+    use crate::mock::msg_at_index;
+    use crate::mock::send_args;
+    use crate::mock::MockContractQuerier;
+    use crate::msg::HandleMsg;
+    use crate::msg::IssueAssetExchange;
+    use crate::msg::QueryMsg;
+    use crate::query::query;
+    use crate::state::capital_call_notice_storage_read;
+    use crate::state::commitment_cap_storage;
+    use crate::state::lockup_storage;
+    use crate::state::redeemed_capital_storage;
+    use crate::state::redemption_cap_storage;
+    use crate::state::rounding_residual_storage_read;
+    use crate::state::tests::asset_exchange_storage_read;
+    use crate::state::tests::set_accepted;
+    use crate::state::State;
+    use crate::subscribe::tests::mock_sub_state;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::to_binary;
+    use cosmwasm_std::Addr;
+    use cosmwasm_std::Attribute;
+    use cosmwasm_std::MemoryStorage;
+    use cosmwasm_std::OwnedDeps;
+    use cosmwasm_std::Timestamp;
+    use cosmwasm_std::{coin, Coin};
+    use provwasm_mocks::ProvenanceMockQuerier;
+
+    // the raise has to hold capital before it can pay any out
+    fn fund_raise(querier: &mut ProvenanceMockQuerier) {
+        querier.base.update_balance(
+            Addr::unchecked(MOCK_CONTRACT_ADDR),
+            coins(1_000_000, "stable_coin"),
+        );
+    }
+
+    #[test]
+    fn size() {
+        let exchange = AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date: Some(ExchangeDate::Available(0)),
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        let as_bytes = to_binary(&exchange).unwrap();
+        println!("{}", std::str::from_utf8(as_bytes.as_slice()).unwrap());
+        assert_eq!(41, as_bytes.len());
+
+        println!("{:?}", from_binary::<AssetExchange>(&as_bytes).unwrap());
+    }
+
+    #[test]
+    fn issue_asset_exchange_for_capital_call() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: None,
+                        commitment_in_shares: Some(1_000),
+                        capital: None,
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+        }
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &vec![]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: AssetExchange {
+                        investment: Some(1_000),
+                        commitment_in_shares: Some(-1_000),
+                        capital: Some(-1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                }],
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+
+        // verify asset exchange appended after the existing commitment
+        assert_eq!(
+            vec![
+                AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(1_000),
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                },
+                AssetExchange {
+                    investment: Some(1_000),
+                    commitment_in_shares: Some(-1_000),
+                    capital: Some(-1_000),
+                    date: None,
+                    issued_at: Some(mock_env().block.time.seconds()),
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                },
+            ],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        )
+    }
+
+    fn issued_redemption(subscription: &str, asset: i64) -> IssueAssetExchange {
+        IssueAssetExchange {
+            subscription: Addr::unchecked(subscription),
+            exchange: AssetExchange {
+                investment: Some(-asset),
+                commitment_in_shares: None,
+                capital: Some(asset * 100),
+                date: Some(ExchangeDate::Available(1672531200)),
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            },
+        }
+    }
+
+    #[test]
+    fn issue_redemptions_skips_duplicates() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![
+                    issued_redemption("sub_1", 1_000),
+                    issued_redemption("sub_1", 1_000),
+                    issued_redemption("sub_1", 500),
+                    issued_redemption("sub_2", 1_000),
+                ],
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+
+        // verify the repeated redemption is only recorded once
+        assert_eq!("skipped_duplicates", res.attributes[0].key);
+        assert_eq!("1", res.attributes[0].value);
+        assert_eq!(
+            2,
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            1,
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_2").as_bytes())
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn issue_redemptions_names_unaccepted_subscription() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![
+                    issued_redemption("sub_1", 1_000),
+                    issued_redemption("sub_2", 1_000),
+                    issued_redemption("sub_3", 1_000),
+                ],
+                idempotency_key: None,
+            },
+        );
+
+        // verify the first offending subscription is named
+        match res.unwrap_err() {
+            ContractError::SubscriptionNotFound { msg } => {
+                assert_eq!("subscription not accepted: sub_2", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn issue_asset_exchange_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &vec![]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![],
+                idempotency_key: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_asset_exchange_not_accepted() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("gp", &vec![]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: AssetExchange {
+                        investment: Some(1_000),
+                        commitment_in_shares: Some(-1_000),
+                        capital: Some(-1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                }],
+                idempotency_key: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_asset_exchanges_replayed_with_idempotency_key() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        let call = AssetExchange {
+            investment: Some(1_000),
+            commitment_in_shares: Some(-1_000),
+            capital: Some(-1_000),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        let issue = || HandleMsg::IssueAssetExchanges {
+            asset_exchanges: vec![IssueAssetExchange {
+                subscription: Addr::unchecked("sub_1"),
+                exchange: call.clone(),
+            }],
+            idempotency_key: Some(String::from("batch_1")),
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), issue()).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), issue()).unwrap();
+
+        // verify the resubmitted batch is a no-op
+        assert_eq!(vec![Attribute::new("replayed", "true")], res.attributes);
+        assert_eq!(
+            1,
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn cancel_asset_exchanges_replayed_with_idempotency_key() {
+        let mut deps = default_deps(None);
+        let redemption = AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![redemption.clone(), redemption.clone()],
+            )
+            .unwrap();
+        let cancel = || HandleMsg::CancelAssetExchanges {
+            cancellations: vec![IssueAssetExchange {
+                subscription: Addr::unchecked("sub_1"),
+                exchange: redemption.clone(),
+            }],
+            idempotency_key: Some(String::from("batch_1")),
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), cancel()).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), cancel()).unwrap();
+
+        // verify only one of the identical redemptions is cancelled
+        assert_eq!(
+            vec![redemption],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn idempotency_keys_forget_oldest() {
+        let mut deps = default_deps(None);
+        for i in 0..=MAX_PROCESSED_IDEMPOTENCY_KEYS {
+            assert!(!is_replay(&mut deps.storage, "issue", &Some(i.to_string())).unwrap());
+        }
+
+        // verify the oldest key is evicted while recent keys are still remembered
+        assert!(!is_replay(&mut deps.storage, "issue", &Some(String::from("0"))).unwrap());
+        assert!(is_replay(
+            &mut deps.storage,
+            "issue",
+            &Some(MAX_PROCESSED_IDEMPOTENCY_KEYS.to_string())
+        )
+        .unwrap());
+        // verify keys are scoped per action
+        assert!(!is_replay(&mut deps.storage, "cancel", &Some(String::from("1"))).unwrap());
+    }
+
+    #[test]
+    fn issue_asset_exchange_far_future_date() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        // a millisecond timestamp mistaken for seconds
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: Some(ExchangeDate::Available(1672531200000)),
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                }],
+                idempotency_key: None,
+            },
+        );
+
+        assert!(res.is_err());
+        assert_eq!(
+            None,
+            asset_exchange_storage_read(&deps.storage)
+                .may_load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn issue_proportional_redemptions() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            for (subscription, shares) in [("sub_1", 100), ("sub_2", 200), ("sub_3", 300)] {
+                storage
+                    .save(
+                        Addr::unchecked(subscription).as_bytes(),
+                        &vec![AssetExchange {
+                            investment: None,
+                            commitment_in_shares: Some(shares),
+                            capital: None,
+                            date: None,
+                            issued_at: None,
+                            installments: vec![],
+                            gp_note: None,
+                            memo: None,
+                            expires_at: None,
+                        }],
+                    )
+                    .unwrap();
+            }
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueProportionalRedemptions {
+                subscriptions: vec!["sub_1", "sub_2", "sub_3"]
+                    .into_iter()
+                    .map(Addr::unchecked)
+                    .collect(),
+                total_capital: 100_000,
+            },
+        )
+        .unwrap();
+
+        // verify each redemption is floored to a whole lot of 100 capital per share
+        let redemption = |subscription: &str| {
+            let exchanges = asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked(subscription).as_bytes())
+                .unwrap();
+            (exchanges[1].investment, exchanges[1].capital)
+        };
+        assert_eq!((Some(-166), Some(16_600)), redemption("sub_1"));
+        assert_eq!((Some(-333), Some(33_300)), redemption("sub_2"));
+        assert_eq!((Some(-500), Some(50_000)), redemption("sub_3"));
+
+        // verify the undistributed capital is reported
+        assert_eq!("leftover_capital", res.attributes[0].key);
+        assert_eq!("100", res.attributes[0].value);
+    }
+
+    #[test]
+    fn issue_proportional_redemptions_after_commitment_settled() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        for subscription in ["sub_1", "sub_2"] {
+            deps.querier
+                .base
+                .update_balance(Addr::unchecked(subscription), coins(100, "commitment_coin"));
+        }
+        called_capital_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_2").as_bytes(), &10_000)
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueProportionalRedemptions {
+                subscriptions: vec!["sub_1", "sub_2"]
+                    .into_iter()
+                    .map(Addr::unchecked)
+                    .collect(),
+                total_capital: 30_000,
+            },
+        )
+        .unwrap();
+
+        // verify settled commitment is redeemed against, with called shares still counted
+        let redeemed = |subscription: &str| {
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked(subscription).as_bytes())
+                .unwrap()[0]
+                .capital
+        };
+        assert_eq!(Some(10_000), redeemed("sub_1"));
+        assert_eq!(Some(20_000), redeemed("sub_2"));
+    }
+
+    #[test]
+    fn issue_proportional_redemptions_overflow() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        deps.querier.base.update_balance(
+            Addr::unchecked("sub_1"),
+            coins(u64::MAX.into(), "commitment_coin"),
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueProportionalRedemptions {
+                subscriptions: vec![Addr::unchecked("sub_1")].into_iter().collect(),
+                total_capital: u64::MAX,
+            },
+        );
+        assert_eq!(
+            "Generic error: proportional redemption overflow",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn issue_proportional_redemptions_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::IssueProportionalRedemptions {
+                subscriptions: HashSet::new(),
+                total_capital: 100_000,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_redemptions_by_commitment_pct() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: None,
+                        commitment_in_shares: Some(1_000),
+                        capital: None,
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_2").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: None,
+                        commitment_in_shares: Some(500),
+                        capital: None,
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+        }
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueRedemptionsByCommitmentPct {
+                subscriptions: vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_2")]
+                    .into_iter()
+                    .collect(),
+                pct_bps: 2_500,
+            },
+        )
+        .unwrap();
+
+        // verify redemptions are 25% of each sub's committed shares
+        assert_eq!(
+            &AssetExchange {
+                investment: Some(-250),
+                commitment_in_shares: None,
+                capital: Some(25_000),
+                date: None,
+                issued_at: Some(mock_env().block.time.seconds()),
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            },
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .last()
+                .unwrap()
+        );
+        assert_eq!(
+            &AssetExchange {
+                investment: Some(-125),
+                commitment_in_shares: None,
+                capital: Some(12_500),
+                date: None,
+                issued_at: Some(mock_env().block.time.seconds()),
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            },
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_2").as_bytes())
+                .unwrap()
+                .last()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn issue_redemptions_by_commitment_pct_after_commitment_settled() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(750, "commitment_coin"));
+        called_capital_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &25_000)
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueRedemptionsByCommitmentPct {
+                subscriptions: vec![Addr::unchecked("sub_1")].into_iter().collect(),
+                pct_bps: 2_500,
+            },
+        )
+        .unwrap();
+
+        // verify held and called commitment both count once no exchanges are queued
+        let redemption = asset_exchange_storage_read(&deps.storage)
+            .load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap();
+        assert_eq!(1, redemption.len());
+        assert_eq!(
+            (Some(-250), Some(25_000)),
+            (redemption[0].investment, redemption[0].capital)
+        );
+    }
+
+    fn issue_half_redemption(deps: DepsMut<ProvenanceQuery>) {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueRedemptionsByCommitmentPct {
+                subscriptions: vec![Addr::unchecked("sub_1")].into_iter().collect(),
+                pct_bps: 5_000,
+            },
+        )
+        .unwrap();
+    }
+
+    fn odd_commitment_deps(
+        rounding: Option<RoundingPolicy>,
+    ) -> OwnedDeps<MockStorage, MockApi, ProvenanceMockQuerier, ProvenanceQuery> {
+        let mut deps = default_deps(None);
+        config(&mut deps.storage)
+            .update(|mut state| -> Result<_, ContractError> {
+                state.redemption_rounding = rounding;
+                Ok(state)
+            })
+            .unwrap();
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(1_005),
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        deps
+    }
+
+    fn last_redeemed_shares(storage: &MockStorage) -> i64 {
+        asset_exchange_storage_read(storage)
+            .load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap()
+            .last()
+            .unwrap()
+            .investment
+            .unwrap()
+    }
+
+    #[test]
+    fn issue_redemptions_by_commitment_pct_carries_residual() {
+        let mut deps = odd_commitment_deps(None);
+
+        issue_half_redemption(deps.as_mut());
+        assert_eq!(-502, last_redeemed_shares(&deps.storage));
+        assert_eq!(
+            5_000,
+            rounding_residual_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+
+        // the carried half share is paid out on the next issuance
+        issue_half_redemption(deps.as_mut());
+        assert_eq!(-503, last_redeemed_shares(&deps.storage));
+        assert_eq!(
+            0,
+            rounding_residual_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn issue_redemptions_by_commitment_pct_nearest() {
+        let mut deps = odd_commitment_deps(Some(RoundingPolicy::Nearest));
+
+        issue_half_redemption(deps.as_mut());
+        assert_eq!(-503, last_redeemed_shares(&deps.storage));
+        assert_eq!(
+            -5_000,
+            rounding_residual_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn issue_redemptions_by_commitment_pct_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::IssueRedemptionsByCommitmentPct {
+                subscriptions: HashSet::new(),
+                pct_bps: 2_500,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn cancel_asset_exchange() {
+        let mut deps = default_deps(None);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: Some(1_000),
+                        commitment_in_shares: Some(-1_000),
+                        capital: Some(-1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+        }
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &vec![]),
+            HandleMsg::CancelAssetExchanges {
+                cancellations: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: AssetExchange {
+                        investment: Some(1_000),
+                        commitment_in_shares: Some(-1_000),
+                        capital: Some(-1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                }],
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+
+        // verify exchange is removed
+        assert_eq!(
+            0,
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .len()
+        )
+    }
+
+    fn memo_redemption(memo: Option<&str>) -> AssetExchange {
+        AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: memo.map(String::from),
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn cancel_asset_exchange_ignores_memo() {
+        let mut deps = default_deps(None);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![memo_redemption(Some("q3 distribution"))],
+            )
+            .unwrap();
+
+        // cancel with a mistyped memo
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::CancelAssetExchanges {
+                cancellations: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: memo_redemption(Some("q3 distributoin")),
+                }],
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+
+        // verify exchange is removed
+        assert!(asset_exchange_storage_read(&deps.storage)
+            .load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn complete_redemption_emits_issued_memo() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![memo_redemption(Some("q3 distribution"))],
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![memo_redemption(None)],
+                to: None,
+                memo: Some(String::from("note")),
+                subscription: None,
+            },
+        )
+        .unwrap();
+
+        // verify the issued memo is emitted alongside the claimant's memo
+        assert_eq!(
+            vec![
+                Attribute::new("issued_memo", "q3 distribution"),
+                Attribute::new("memo", "note"),
+            ],
+            res.attributes
+        );
+    }
+
+    #[test]
+    fn cancel_asset_exchange_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &vec![]),
+            HandleMsg::CancelAssetExchanges {
+                cancellations: vec![],
+                idempotency_key: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn cancel_asset_exchange_not_found() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("gp", &coins(10_000, "stable_coin")),
+            HandleMsg::CancelAssetExchanges {
+                cancellations: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: AssetExchange {
+                        investment: Some(1_000),
+                        commitment_in_shares: Some(-1_000),
+                        capital: Some(-1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                }],
+                idempotency_key: None,
+            },
+        );
+
+        match res.unwrap_err() {
+            ContractError::AssetExchangeNotFound { msg } => {
+                assert_eq!("no asset exchange found for subscription", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn complete_asset_exchange() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![
+                        AssetExchange {
+                            investment: Some(-1_000),
+                            commitment_in_shares: None,
+                            capital: Some(1_000),
+                            date: None,
+                            issued_at: None,
+                            installments: vec![],
+                            gp_note: None,
+                            memo: None,
+                            expires_at: None,
+                        },
+                        AssetExchange {
+                            investment: Some(-1_000),
+                            commitment_in_shares: None,
+                            capital: Some(1_000),
+                            date: None,
+                            issued_at: None,
+                            installments: vec![],
+                            gp_note: None,
+                            memo: None,
+                            expires_at: None,
+                        },
+                    ],
+                )
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(2_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![
+                    AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                    AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                ],
+                to: Some(Addr::unchecked("destination")),
+                memo: Some(String::from("note")),
+                subscription: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(3, res.messages.len());
+
+        // verify memo
+        assert_eq!(1, res.attributes.len());
+        let attribute = res.attributes.get(0).unwrap();
+        assert_eq!("memo", attribute.key);
+        assert_eq!("note", attribute.value);
+
+        // verify deposit investment
+        let (to_address, coins) = send_args(msg_at_index(&res, 0));
+        let coin = coins.first().unwrap();
+        assert_eq!("tp18vd8fpwxzck93qlwghaj6arh4p7c5n89x8kskz", to_address);
+        assert_eq!("investment_coin", coin.denom);
+        assert_eq!(2_000, coin.amount.u128());
+
+        // verify burn investment
+        let coin = burn_args(msg_at_index(&res, 1));
+        assert_eq!("investment_coin", coin.denom);
+        assert_eq!(2_000, coin.amount.u128());
+
+        // verify send message
+        let (to_address, coins) = send_args(msg_at_index(&res, 2));
+        let coin = coins.first().unwrap();
+        assert_eq!("destination", to_address);
+        assert_eq!("stable_coin", coin.denom);
+        assert_eq!(2_000, coin.amount.u128());
+
+        // verify exchange is removed
+        assert_eq!(
+            0,
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .len()
+        )
+    }
+
+    #[test]
+    fn complete_asset_exchange_without_asset() {
+        let mut deps = default_deps(None);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &vec![]),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: Some(Addr::unchecked("destination")),
+                memo: Some(String::from("note")),
+                subscription: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn claim_asset_exchange_not_available_yet() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: Some(ExchangeDate::Available(1675209600)), // Feb 01 2023 UTC
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+        }
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1672531200); // Jan 01 2023 UTC
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: Some(Addr::unchecked("destination")),
+                memo: Some(String::from("note")),
+                subscription: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn complete_asset_exchange_during_lockup() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+            lockup_storage(&mut deps.storage)
+                .save(Addr::unchecked("sub_1").as_bytes(), &1675209600) // Feb 01 2023 UTC
+                .unwrap();
+        }
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1672531200); // Jan 01 2023 UTC
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn complete_asset_exchange_after_lockup() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+            lockup_storage(&mut deps.storage)
+                .save(Addr::unchecked("sub_1").as_bytes(), &1672531200) // Jan 01 2023 UTC
+                .unwrap();
+        }
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1675209600); // Feb 01 2023 UTC
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        )
+        .unwrap();
+
+        // verify deposit, burn, and capital send
+        assert_eq!(3, res.messages.len());
+    }
+
+    #[test]
+    fn complete_asset_exchange_while_redemptions_paused() {
+        let mut deps = default_deps(Some(|state| state.redemptions_paused = true));
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![AssetExchange {
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    }],
+                )
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn cancel_redemptions_older_than() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        let redemption = |issued_at: Option<u64>| AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date: None,
+            issued_at,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![
+                        redemption(Some(1672531200)), // Jan 01 2023 UTC
+                        redemption(Some(1675209600)), // Feb 01 2023 UTC
+                        redemption(None),
+                        AssetExchange {
+                            investment: None,
+                            commitment_in_shares: Some(1_000),
+                            capital: None,
+                            date: None,
+                            issued_at: Some(1672531200), // Jan 01 2023 UTC
+                            installments: vec![],
+                            gp_note: None,
+                            memo: None,
+                            expires_at: None,
+                        },
+                    ],
+                )
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::CancelRedemptionsOlderThan {
+                issued_before_epoch_seconds: 1673740800, // Jan 15 2023 UTC
+            },
+        )
+        .unwrap();
+
+        // verify cancelled count
+        assert_eq!("cancelled", res.attributes[0].key);
+        assert_eq!("1", res.attributes[0].value);
+
+        // verify only the old redemption is removed
+        assert_eq!(
+            vec![
+                redemption(Some(1675209600)),
+                redemption(None),
+                AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(1_000),
+                    capital: None,
+                    date: None,
+                    issued_at: Some(1672531200),
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                },
+            ],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn set_availability_for_subscriptions() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+        let redemption = |date: Option<ExchangeDate>| AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            for subscription in ["sub_1", "sub_2", "sub_3"] {
+                storage
+                    .save(
+                        Addr::unchecked(subscription).as_bytes(),
+                        &vec![redemption(None)],
+                    )
+                    .unwrap();
+            }
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetAvailabilityForSubscriptions {
+                subscriptions: vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_2")]
+                    .into_iter()
+                    .collect(),
+                available_epoch_seconds: 1675209600, // Feb 01 2023 UTC
+            },
+        )
+        .unwrap();
+
+        // verify updated count
+        assert_eq!("updated", res.attributes[0].key);
+        assert_eq!("2", res.attributes[0].value);
+
+        // verify only the named subscriptions are updated
+        let exchanges = |subscription: &str| {
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked(subscription).as_bytes())
+                .unwrap()
+        };
+        assert_eq!(
+            vec![redemption(Some(ExchangeDate::Available(1675209600)))],
+            exchanges("sub_1")
+        );
+        assert_eq!(
+            vec![redemption(Some(ExchangeDate::Available(1675209600)))],
+            exchanges("sub_2")
+        );
+        assert_eq!(vec![redemption(None)], exchanges("sub_3"));
+    }
+
+    #[test]
+    fn set_availability_for_subscriptions_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetAvailabilityForSubscriptions {
+                subscriptions: HashSet::new(),
+                available_epoch_seconds: 1675209600,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn cancel_redemptions_older_than_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::CancelRedemptionsOlderThan {
+                issued_before_epoch_seconds: 1673740800,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn emergency_halt() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        let redemption = |capital: i64| AssetExchange {
+            investment: Some(-capital),
+            commitment_in_shares: None,
+            capital: Some(capital),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        let capital_call = AssetExchange {
+            investment: None,
+            commitment_in_shares: Some(1_000),
+            capital: Some(-1_000),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![redemption(1_000), capital_call.clone(), redemption(500)],
+                )
+                .unwrap();
+            storage
+                .save(Addr::unchecked("sub_2").as_bytes(), &vec![redemption(250)])
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            HandleMsg::EmergencyHalt {},
+        )
+        .unwrap();
+
+        // verify summary of what was cancelled
+        assert_eq!(
+            vec![
+                Attribute::new("action", "emergency_halt"),
+                Attribute::new("cancelled", "3"),
+                Attribute::new("cancelled_capital", "1750"),
+                Attribute::new("subscriptions", "2"),
+            ],
+            res.attributes
+        );
+
+        // verify only redemptions are cleared
+        assert_eq!(
+            vec![capital_call.clone()],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+        assert_eq!(
+            Vec::<AssetExchange>::new(),
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_2").as_bytes())
+                .unwrap()
+        );
+
+        // verify raise is paused, including cancels
+        assert!(config_read(&deps.storage).load().unwrap().paused);
+        for msg in [
+            HandleMsg::IssueAssetExchanges {
                 asset_exchanges: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_2"),
+                    exchange: redemption(250),
+                }],
+                idempotency_key: None,
+            },
+            HandleMsg::CancelAssetExchanges {
+                cancellations: vec![IssueAssetExchange {
                     subscription: Addr::unchecked("sub_1"),
-                    exchange: AssetExchange {
-                        investment: Some(1_000),
-                        commitment_in_shares: Some(-1_000),
-                        capital: Some(-1_000),
-                        date: None,
-                    },
+                    exchange: capital_call.clone(),
+                }],
+                idempotency_key: None,
+            },
+        ] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), msg);
+            assert_eq!("contract is paused", res.unwrap_err().to_string());
+        }
+    }
+
+    #[test]
+    fn emergency_halt_by_gp() {
+        let mut deps = default_deps(None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::EmergencyHalt {},
+        )
+        .unwrap();
+
+        // verify the gp halt pauses every action without the admin switch
+        let state = config_read(&deps.storage).load().unwrap();
+        assert!(!state.paused);
+        assert_eq!(ActionKind::all(), state.paused_actions);
+        let propose = || HandleMsg::ProposeSubscription {
+            initial_commitment: Some(100),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("lp", &[]), propose());
+        assert_eq!("action is paused", res.unwrap_err().to_string());
+
+        // verify the gp can lift its own halt
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetPaused { paused: false },
+        )
+        .unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("lp", &[]), propose()).unwrap();
+    }
+
+    #[test]
+    fn emergency_halt_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::EmergencyHalt {},
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_and_claim_distribution() {
+        let mut deps = default_deps(None);
+        fund_raise(&mut deps.querier);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        let now = mock_env().block.time.seconds();
+        let distribution = |capital: u64, available_epoch_seconds: u64| Distribution {
+            subscription: Addr::unchecked("sub_1"),
+            capital,
+            available_epoch_seconds,
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueDistributions {
+                distributions: vec![
+                    distribution(1_000, now - 1),
+                    distribution(500, now),
+                    distribution(2_000, now + 1),
+                ],
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::ClaimDistribution {},
+        )
+        .unwrap();
+
+        // verify only available capital is sent and no investment is burned
+        assert_eq!(1, res.messages.len());
+        let (to_address, coins) = send_args(msg_at_index(&res, 0));
+        assert_eq!("sub_1", to_address);
+        assert_eq!(1_500, coins.first().unwrap().amount.u128());
+        assert_eq!("stable_coin", coins.first().unwrap().denom);
+
+        // verify the unavailable distribution remains outstanding
+        assert_eq!(
+            vec![distribution(2_000, now + 1)],
+            outstanding_distributions_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+
+        // verify nothing further can be claimed until it is available
+        assert!(execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::ClaimDistribution {},
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn claim_distribution_not_found() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::ClaimDistribution {},
+        );
+        match res.unwrap_err() {
+            ContractError::DistributionNotFound { msg } => {
+                assert_eq!("no distribution found for subscription", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn claim_distribution_underfunded_raise() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        outstanding_distributions(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![Distribution {
+                    subscription: Addr::unchecked("sub_1"),
+                    capital: 1_000,
+                    available_epoch_seconds: 0,
+                }],
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::ClaimDistribution {},
+        );
+        match res.unwrap_err() {
+            ContractError::InsufficientFunds { msg } => {
+                assert_eq!("insufficient capital in raise to fund distribution", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn claim_distribution_overflow() {
+        let mut deps = default_deps(None);
+        fund_raise(&mut deps.querier);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        let distribution = Distribution {
+            subscription: Addr::unchecked("sub_1"),
+            capital: u64::MAX,
+            available_epoch_seconds: 0,
+        };
+        outstanding_distributions(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![distribution.clone(), distribution],
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::ClaimDistribution {},
+        );
+        assert_eq!(
+            "Generic error: distribution overflow",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn issue_distributions_not_accepted() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::IssueDistributions {
+                distributions: vec![Distribution {
+                    subscription: Addr::unchecked("sub_1"),
+                    capital: 1_000,
+                    available_epoch_seconds: 0,
+                }],
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_distributions_bad_actor() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::IssueDistributions {
+                distributions: vec![Distribution {
+                    subscription: Addr::unchecked("sub_1"),
+                    capital: 1_000,
+                    available_epoch_seconds: 0,
+                }],
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    fn redemption_destination_deps(
+        restrict_redemption_destination: bool,
+    ) -> OwnedDeps<MemoryStorage, MockApi, MockContractQuerier, ProvenanceQuery> {
+        let mut deps = mock_sub_state();
+        load_markers(&mut deps.querier.base);
+        fund_raise(&mut deps.querier.base);
+        config(&mut deps.storage)
+            .save(&State {
+                restrict_redemption_destination,
+                ..State::test_default()
+            })
+            .unwrap();
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![destination_redemption()],
+            )
+            .unwrap();
+        deps
+    }
+
+    fn destination_redemption() -> AssetExchange {
+        AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        }
+    }
+
+    fn claim_redemption_to(to: &str) -> HandleMsg {
+        HandleMsg::CompleteAssetExchange {
+            exchanges: vec![destination_redemption()],
+            to: Some(Addr::unchecked(to)),
+            memo: None,
+            subscription: None,
+        }
+    }
+
+    #[test]
+    fn complete_redemption_restricted_destination() {
+        for destination in ["sub_1", "lp"] {
+            let mut deps = redemption_destination_deps(true);
+
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("sub_1", &coins(1_000, "investment_coin")),
+                claim_redemption_to(destination),
+            )
+            .unwrap();
+
+            // verify capital is sent to the allowed destination
+            let (to_address, _) = send_args(msg_at_index(&res, 2));
+            assert_eq!(destination, to_address);
+        }
+
+        let mut deps = redemption_destination_deps(true);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            claim_redemption_to("attacker"),
+        );
+        assert!(res.is_err());
+
+        // verify the redemption is still outstanding
+        assert_eq!(
+            vec![destination_redemption()],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn complete_redemption_unrestricted_destination() {
+        for destination in ["sub_1", "lp", "attacker"] {
+            let mut deps = redemption_destination_deps(false);
+
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("sub_1", &coins(1_000, "investment_coin")),
+                claim_redemption_to(destination),
+            )
+            .unwrap();
+
+            // verify capital is sent wherever the subscription directs it
+            let (to_address, _) = send_args(msg_at_index(&res, 2));
+            assert_eq!(destination, to_address);
+        }
+    }
+
+    fn complete_redemption_with_funds(funds: &[Coin]) -> ContractResponse {
+        let mut deps = redemption_destination_deps(false);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", funds),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![destination_redemption()],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        )
+    }
+
+    #[test]
+    fn complete_redemption_with_exact_investment() {
+        let res = complete_redemption_with_funds(&coins(1_000, "investment_coin")).unwrap();
+
+        // verify investment is burned and capital sent
+        assert_eq!(3, res.messages.len());
+    }
+
+    #[test]
+    fn complete_redemption_without_funds() {
+        assert!(complete_redemption_with_funds(&[]).is_err());
+    }
+
+    #[test]
+    fn complete_redemption_with_wrong_funds() {
+        assert!(complete_redemption_with_funds(&coins(1_000, "stable_coin")).is_err());
+        assert!(complete_redemption_with_funds(&coins(999, "investment_coin")).is_err());
+    }
+
+    #[test]
+    fn complete_redemption_with_extra_coins() {
+        let res =
+            complete_redemption_with_funds(&[coin(1_000, "investment_coin"), coin(1, "junk_coin")]);
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn complete_redemption_underfunded_raise() {
+        let mut deps = redemption_destination_deps(false);
+        deps.querier.base.base.update_balance(
+            Addr::unchecked(MOCK_CONTRACT_ADDR),
+            coins(999, "stable_coin"),
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![destination_redemption()],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        );
+        match res.unwrap_err() {
+            ContractError::InsufficientFunds { msg } => {
+                assert_eq!("insufficient capital in raise to fund redemption", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    fn complete_expiring_redemption(expires_at: u64) -> ContractResponse {
+        let mut deps = redemption_destination_deps(false);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    expires_at: Some(expires_at),
+                    ..destination_redemption()
+                }],
+            )
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![destination_redemption()],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        )
+    }
+
+    #[test]
+    fn complete_redemption_before_expiry() {
+        let now = mock_env().block.time.seconds();
+
+        // verify investment is burned and capital sent
+        assert_eq!(
+            3,
+            complete_expiring_redemption(now + 1)
+                .unwrap()
+                .messages
+                .len()
+        );
+    }
+
+    #[test]
+    fn complete_redemption_after_expiry() {
+        let now = mock_env().block.time.seconds();
+
+        assert_eq!(
+            "Generic error: redemption expired",
+            complete_expiring_redemption(now).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn sweep_expired_redemptions() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        let now = mock_env().block.time.seconds();
+        let redemption = |expires_at: Option<u64>| AssetExchange {
+            expires_at,
+            ..destination_redemption()
+        };
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![
+                        redemption(Some(now - 100)),
+                        redemption(Some(now + 100)),
+                        redemption(None),
+                    ],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_2").as_bytes(),
+                    &vec![redemption(Some(now))],
+                )
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SweepExpiredRedemptions {},
+        )
+        .unwrap();
+
+        // verify only expired redemptions are removed and counted
+        assert_eq!(
+            &Attribute::new("swept", "2"),
+            res.attributes.get(1).unwrap()
+        );
+        assert_eq!(
+            vec![redemption(Some(now + 100)), redemption(None)],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+        assert!(asset_exchange_storage_read(&deps.storage)
+            .load(Addr::unchecked("sub_2").as_bytes())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn sweep_expired_redemptions_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SweepExpiredRedemptions {},
+        );
+        assert!(res.is_err());
+    }
+
+    fn lp_claim_deps(
+        allow_lp_claims: bool,
+    ) -> OwnedDeps<MemoryStorage, MockApi, MockContractQuerier, ProvenanceQuery> {
+        let mut deps = mock_sub_state();
+        load_markers(&mut deps.querier.base);
+        fund_raise(&mut deps.querier.base);
+        config(&mut deps.storage)
+            .save(&State {
+                allow_lp_claims,
+                ..State::test_default()
+            })
+            .unwrap();
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        deps
+    }
+
+    fn claim_capital(sender: &str) -> (MessageInfo, HandleMsg) {
+        (
+            mock_info(sender, &[]),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: None,
+                memo: None,
+                subscription: Some(Addr::unchecked("sub_1")),
+            },
+        )
+    }
+
+    #[test]
+    fn complete_asset_exchange_as_subscription() {
+        let mut deps = lp_claim_deps(false);
+        let (info, msg) = claim_capital("sub_1");
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // verify capital is sent to subscription
+        let (to_address, _) = send_args(msg_at_index(&res, 0));
+        assert_eq!("sub_1", to_address);
+    }
+
+    #[test]
+    fn complete_asset_exchange_as_lp() {
+        let mut deps = lp_claim_deps(true);
+        let (info, msg) = claim_capital("lp");
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // verify capital is sent to subscription
+        let (to_address, _) = send_args(msg_at_index(&res, 0));
+        assert_eq!("sub_1", to_address);
+
+        // verify exchange is removed
+        assert_eq!(
+            0,
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn complete_asset_exchange_as_lp_not_allowed() {
+        let mut deps = lp_claim_deps(false);
+        let (info, msg) = claim_capital("lp");
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn complete_asset_exchange_as_unrelated_address() {
+        let mut deps = lp_claim_deps(true);
+        let (info, msg) = claim_capital("bad_actor");
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reduce_commitment() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(1_000),
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::ReduceCommitment {
+                subscription: Addr::unchecked("sub_1"),
+                reduce_shares: 400,
+            },
+        )
+        .unwrap();
+
+        // verify net committed shares are reduced
+        let net_shares: i64 = asset_exchange_storage_read(&deps.storage)
+            .load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap()
+            .iter()
+            .filter_map(|e| e.commitment_in_shares)
+            .sum();
+        assert_eq!(600, net_shares);
+
+        // verify reduction beyond the net position is rejected
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::ReduceCommitment {
+                subscription: Addr::unchecked("sub_1"),
+                reduce_shares: 601,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reduce_commitment_held_by_sub() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(1_000, "commitment_coin"));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::ReduceCommitment {
+                subscription: Addr::unchecked("sub_1"),
+                reduce_shares: 400,
+            },
+        )
+        .unwrap();
+
+        // verify the pending reduction counts against the held commitment
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::ReduceCommitment {
+                subscription: Addr::unchecked("sub_1"),
+                reduce_shares: 601,
+            },
+        );
+        assert_eq!(
+            "commitment reduction exceeds net committed shares",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    fn capital_call_deps() -> OwnedDeps<MockStorage, MockApi, ProvenanceMockQuerier, ProvenanceQuery>
+    {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(1_000),
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        deps
+    }
+
+    fn capital_call(amount: u64) -> HandleMsg {
+        HandleMsg::IssueCapitalCalls {
+            calls: vec![CapitalCall {
+                subscription: Addr::unchecked("sub_1"),
+                amount,
+            }],
+        }
+    }
+
+    #[test]
+    fn issue_capital_calls() {
+        let mut deps = capital_call_deps();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(25_000),
+        )
+        .unwrap();
+
+        // verify drawdown is appended after the commitment
+        assert_eq!(
+            &AssetExchange {
+                investment: Some(250),
+                commitment_in_shares: Some(-250),
+                capital: Some(-25_000),
+                date: Some(ExchangeDate::Available(mock_env().block.time.seconds())),
+                issued_at: Some(mock_env().block.time.seconds()),
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            },
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+                .last()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn issue_capital_calls_uneven_amount() {
+        let res = execute(
+            capital_call_deps().as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(25_050),
+        );
+
+        assert!(matches!(res, Err(ContractError::NotDivisible { .. })));
+    }
+
+    #[test]
+    fn issue_capital_calls_exceeding_commitment() {
+        let res = execute(
+            capital_call_deps().as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(100_100),
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_capital_calls_after_commitment_completed() {
+        let mut deps = capital_call_deps();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(1_000),
+                    capital: None,
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
                 }],
+                to: None,
+                memo: None,
+                subscription: None,
             },
         )
         .unwrap();
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(1_000, "commitment_coin"));
 
-        // verify asset exchange added
+        // verify the call is checked against the commitment now held by the sub
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(25_000),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(80_000),
+        );
         assert_eq!(
-            2,
-            asset_exchange_storage_read(&deps.storage)
+            "capital call exceeds remaining committed shares",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn issue_capital_calls_exceeding_commitment_cap() {
+        let mut deps = capital_call_deps();
+        commitment_cap_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &40_000)
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(25_000),
+        )
+        .unwrap();
+
+        // verify outstanding calls count toward the cap
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(20_000),
+        );
+        match res.unwrap_err() {
+            ContractError::LimitExceeded { msg } => {
+                assert_eq!("capital call exceeds commitment cap", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn issue_capital_calls_exceeding_commitment_cap_after_paid_call() {
+        let mut deps = capital_call_deps();
+        load_markers(&mut deps.querier);
+        commitment_cap_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &40_000)
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(25_000),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &[]),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![AssetExchange {
+                    investment: Some(250),
+                    commitment_in_shares: Some(-250),
+                    capital: Some(-25_000),
+                    date: Some(ExchangeDate::Available(mock_env().block.time.seconds())),
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        )
+        .unwrap();
+
+        // verify the paid call still counts toward the cap
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            capital_call(20_000),
+        );
+        assert_eq!(
+            "capital call exceeds commitment cap",
+            res.unwrap_err().to_string()
+        );
+        assert_eq!(
+            25_000,
+            called_capital_storage_read(&deps.storage)
                 .load(Addr::unchecked("sub_1").as_bytes())
                 .unwrap()
-                .len()
-        )
+        );
     }
 
     #[test]
-    fn issue_asset_exchange_bad_actor() {
+    fn issue_capital_calls_not_accepted() {
         let res = execute(
             default_deps(None).as_mut(),
             mock_env(),
-            mock_info("bad_actor", &vec![]),
-            HandleMsg::IssueAssetExchanges {
-                asset_exchanges: vec![],
+            mock_info("gp", &[]),
+            capital_call(25_000),
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn issue_capital_calls_bad_actor() {
+        let res = execute(
+            capital_call_deps().as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            capital_call(25_000),
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reduce_commitment_bad_actor() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::ReduceCommitment {
+                subscription: Addr::unchecked("sub_1"),
+                reduce_shares: 400,
             },
         );
 
@@ -299,268 +3981,659 @@ pub mod tests {
     }
 
     #[test]
-    fn issue_asset_exchange_not_accepted() {
+    fn emit_statement() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        // the accept and a reduction have already settled into the sub's balance
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(800, "commitment_coin"));
+        called_capital_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &50_000)
+            .unwrap();
+        let redemption = AssetExchange {
+            investment: Some(-100),
+            commitment_in_shares: None,
+            capital: Some(10_000),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![
+                    AssetExchange {
+                        investment: Some(500),
+                        commitment_in_shares: Some(-500),
+                        capital: Some(-50_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
+                    redemption.clone(),
+                    redemption.clone(),
+                ],
+            )
+            .unwrap();
+
+        // complete one of the redemptions
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(100, "investment_coin")),
+            HandleMsg::CompleteAssetExchange {
+                exchanges: vec![redemption],
+                to: None,
+                memo: None,
+                subscription: None,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::EmitStatement {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        )
+        .unwrap();
+
+        // verify statement attributes
+        let attribute = |key: &str| {
+            res.attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+        assert_eq!("sub_1", attribute("subscription"));
+        // 300 still uncalled plus 500 called
+        assert_eq!("800", attribute("committed_shares"));
+        assert_eq!("50000", attribute("called_capital"));
+        assert_eq!("10000", attribute("redeemed_capital"));
+        assert_eq!("10000", attribute("outstanding_redemptions"));
+    }
+
+    #[test]
+    fn reemit_redemption_events() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        let redemption = |asset: i64, capital: i64| AssetExchange {
+            investment: Some(-asset),
+            commitment_in_shares: None,
+            capital: Some(capital),
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![
+                        redemption(1_000, 100_000),
+                        AssetExchange {
+                            investment: None,
+                            commitment_in_shares: Some(1_000),
+                            capital: None,
+                            date: None,
+                            issued_at: None,
+                            installments: vec![],
+                            gp_note: None,
+                            memo: None,
+                            expires_at: None,
+                        },
+                    ],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_2").as_bytes(),
+                    &vec![redemption(500, 50_000)],
+                )
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::ReemitRedemptionEvents {},
+        )
+        .unwrap();
+
+        // verify one event per outstanding redemption
+        assert_eq!(
+            vec![
+                Event::new("redemption")
+                    .add_attribute("subscription", "sub_1")
+                    .add_attribute("asset", "1000")
+                    .add_attribute("capital", "100000"),
+                Event::new("redemption")
+                    .add_attribute("subscription", "sub_2")
+                    .add_attribute("asset", "500")
+                    .add_attribute("capital", "50000"),
+            ],
+            res.events
+        );
+        assert_eq!(0, res.messages.len());
+    }
+
+    #[test]
+    fn reemit_redemption_events_bad_actor() {
         let res = execute(
             default_deps(None).as_mut(),
             mock_env(),
-            mock_info("gp", &vec![]),
+            mock_info("bad_actor", &[]),
+            HandleMsg::ReemitRedemptionEvents {},
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn emit_statement_bad_actor() {
+        let mut deps = lp_claim_deps(false);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::EmitStatement {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn set_redemption_note() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        // issue a redemption with a note
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
             HandleMsg::IssueAssetExchanges {
                 asset_exchanges: vec![IssueAssetExchange {
                     subscription: Addr::unchecked("sub_1"),
                     exchange: AssetExchange {
-                        investment: Some(1_000),
-                        commitment_in_shares: Some(-1_000),
-                        capital: Some(-1_000),
+                        investment: Some(-1_000),
+                        commitment_in_shares: None,
+                        capital: Some(1_000),
                         date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: Some(String::from("q1 liquidity event")),
+                        memo: None,
+                        expires_at: None,
+                    },
+                }],
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+
+        let note =
+            |deps: &OwnedDeps<MockStorage, MockApi, ProvenanceMockQuerier, ProvenanceQuery>| {
+                let res = query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetAssetExchangesForSubscription {
+                        subscription: Addr::unchecked("sub_1"),
                     },
+                )
+                .unwrap();
+                let exchanges: Option<Vec<AssetExchange>> = from_binary(&res).unwrap();
+                exchanges.unwrap()[0].gp_note.clone()
+            };
+
+        // verify the note is returned with the redemption
+        assert_eq!(Some(String::from("q1 liquidity event")), note(&deps));
+
+        // edit the note
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetRedemptionNote {
+                subscription: Addr::unchecked("sub_1"),
+                asset: 1_000,
+                capital: 1_000,
+                gp_note: Some(String::from("deferred to q2")),
+            },
+        )
+        .unwrap();
+        assert_eq!(Some(String::from("deferred to q2")), note(&deps));
+
+        // clear the note
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetRedemptionNote {
+                subscription: Addr::unchecked("sub_1"),
+                asset: 1_000,
+                capital: 1_000,
+                gp_note: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(None, note(&deps));
+    }
+
+    #[test]
+    fn set_redemption_note_bad_actor() {
+        let mut deps = default_deps(None);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
                 }],
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::SetRedemptionNote {
+                subscription: Addr::unchecked("sub_1"),
+                asset: 1_000,
+                capital: 1_000,
+                gp_note: Some(String::from("note")),
             },
         );
+        assert!(res.is_err());
+    }
+
+    fn capped_redemption_deps(
+        redeemed: u64,
+    ) -> OwnedDeps<MockStorage, MockApi, ProvenanceMockQuerier, ProvenanceQuery> {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        redemption_cap_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &10_000)
+            .unwrap();
+        redeemed_capital_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &redeemed)
+            .unwrap();
+        deps
+    }
+
+    fn claim_redemption() -> HandleMsg {
+        HandleMsg::CompleteAssetExchange {
+            exchanges: vec![AssetExchange {
+                investment: Some(-1_000),
+                commitment_in_shares: None,
+                capital: Some(1_000),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            }],
+            to: None,
+            memo: None,
+            subscription: None,
+        }
+    }
+
+    #[test]
+    fn complete_redemption_within_cap() {
+        let mut deps = capped_redemption_deps(9_000);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            claim_redemption(),
+        )
+        .unwrap();
+
+        // verify cumulative redeemed capital reaches the cap
+        assert_eq!(
+            10_000,
+            redeemed_capital_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn complete_redemption_beyond_cap() {
+        let mut deps = capped_redemption_deps(9_500);
 
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            claim_redemption(),
+        );
         assert!(res.is_err());
     }
 
     #[test]
-    fn cancel_asset_exchange() {
+    fn notice_capital_call() {
         let mut deps = default_deps(None);
-        {
-            asset_exchange_storage(&mut deps.storage)
-                .save(
-                    Addr::unchecked("sub_1").as_bytes(),
-                    &vec![AssetExchange {
-                        investment: Some(1_000),
-                        commitment_in_shares: Some(-1_000),
-                        capital: Some(-1_000),
-                        date: None,
-                    }],
-                )
-                .unwrap();
-        }
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
 
-        execute(
+        let res = execute(
             deps.as_mut(),
             mock_env(),
-            mock_info("gp", &vec![]),
-            HandleMsg::CancelAssetExchanges {
-                cancellations: vec![IssueAssetExchange {
-                    subscription: Addr::unchecked("sub_1"),
-                    exchange: AssetExchange {
-                        investment: Some(1_000),
-                        commitment_in_shares: Some(-1_000),
-                        capital: Some(-1_000),
-                        date: None,
-                    },
-                }],
+            mock_info("gp", &[]),
+            HandleMsg::NoticeCapitalCall {
+                subscriptions: vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_2")]
+                    .into_iter()
+                    .collect(),
+                amount: 10_000,
+                effective_epoch_seconds: 1675209600, // Feb 01 2023 UTC
+                memo: Some(String::from("q1 call")),
             },
         )
         .unwrap();
 
-        // verify exchange is removed
+        // verify no funds move
+        assert_eq!(0, res.messages.len());
+
+        // verify notice attributes
+        let notices: Vec<&str> = res
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.key == "capital_call_notice")
+            .map(|attribute| attribute.value.as_str())
+            .collect();
+        assert_eq!(vec!["sub_1", "sub_2"], notices);
+
+        // verify stored notices
+        for subscription in ["sub_1", "sub_2"] {
+            assert_eq!(
+                vec![CapitalCallNotice {
+                    amount: 10_000,
+                    effective_epoch_seconds: 1675209600,
+                    memo: Some(String::from("q1 call")),
+                }],
+                capital_call_notice_storage_read(&deps.storage)
+                    .load(Addr::unchecked(subscription).as_bytes())
+                    .unwrap()
+            );
+        }
+
+        // verify no asset exchanges are issued
         assert_eq!(
-            0,
+            None,
             asset_exchange_storage_read(&deps.storage)
-                .load(Addr::unchecked("sub_1").as_bytes())
+                .may_load(Addr::unchecked("sub_1").as_bytes())
                 .unwrap()
-                .len()
-        )
+        );
     }
 
     #[test]
-    fn cancel_asset_exchange_bad_actor() {
+    fn notice_capital_call_bad_actor() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
         let res = execute(
-            default_deps(None).as_mut(),
+            deps.as_mut(),
             mock_env(),
-            mock_info("bad_actor", &vec![]),
-            HandleMsg::CancelAssetExchanges {
-                cancellations: vec![],
+            mock_info("bad_actor", &[]),
+            HandleMsg::NoticeCapitalCall {
+                subscriptions: vec![Addr::unchecked("sub_1")].into_iter().collect(),
+                amount: 10_000,
+                effective_epoch_seconds: 1675209600,
+                memo: None,
             },
         );
-
         assert!(res.is_err());
     }
 
     #[test]
-    fn cancel_asset_exchange_not_found() {
+    fn complete_asset_exchange_while_frozen() {
+        let mut deps = default_deps(None);
+        load_markers(&mut deps.querier);
+        fund_raise(&mut deps.querier);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        let freeze = |frozen: bool| HandleMsg::FreezeSubscription {
+            subscription: Addr::unchecked("sub_1"),
+            frozen,
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            freeze(true),
+        )
+        .unwrap();
+
+        // verify claim is blocked while frozen
         let res = execute(
-            default_deps(None).as_mut(),
+            deps.as_mut(),
             mock_env(),
-            mock_info("gp", &coins(10_000, "stable_coin")),
-            HandleMsg::CancelAssetExchanges {
-                cancellations: vec![IssueAssetExchange {
-                    subscription: Addr::unchecked("sub_1"),
-                    exchange: AssetExchange {
-                        investment: Some(1_000),
-                        commitment_in_shares: Some(-1_000),
-                        capital: Some(-1_000),
-                        date: None,
-                    },
-                }],
-            },
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            claim_redemption(),
         );
+        match res.unwrap_err() {
+            ContractError::Frozen { msg } => assert_eq!("subscription is frozen", msg),
+            err => panic!("unexpected error: {}", err),
+        }
 
-        assert!(res.is_err());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            freeze(false),
+        )
+        .unwrap();
+
+        // verify claim succeeds once unfrozen
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sub_1", &coins(1_000, "investment_coin")),
+            claim_redemption(),
+        )
+        .unwrap();
     }
 
     #[test]
-    fn complete_asset_exchange() {
+    fn complete_redemption_in_installments() {
         let mut deps = default_deps(None);
         load_markers(&mut deps.querier);
-        {
-            asset_exchange_storage(&mut deps.storage)
-                .save(
-                    Addr::unchecked("sub_1").as_bytes(),
-                    &vec![
-                        AssetExchange {
-                            investment: Some(-1_000),
-                            commitment_in_shares: None,
-                            capital: Some(1_000),
-                            date: None,
-                        },
-                        AssetExchange {
-                            investment: Some(-1_000),
-                            commitment_in_shares: None,
-                            capital: Some(1_000),
-                            date: None,
-                        },
+        fund_raise(&mut deps.querier);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(100_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![
+                        (1672531200, 400), // Jan 01 2023 UTC
+                        (1677628800, 600), // Mar 01 2023 UTC
                     ],
-                )
-                .unwrap();
-        }
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+        let claim = |investment: i64, capital: i64| HandleMsg::CompleteAssetExchange {
+            exchanges: vec![AssetExchange {
+                investment: Some(investment),
+                commitment_in_shares: None,
+                capital: Some(capital),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            }],
+            to: None,
+            memo: None,
+            subscription: None,
+        };
 
+        // claim the first installment
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1675209600); // Feb 01 2023 UTC
         let res = execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info("sub_1", &coins(1_000, "investment_coin")),
-            HandleMsg::CompleteAssetExchange {
-                exchanges: vec![
-                    AssetExchange {
-                        investment: Some(-1_000),
-                        commitment_in_shares: None,
-                        capital: Some(1_000),
-                        date: None,
-                    },
-                    AssetExchange {
-                        investment: Some(-1_000),
-                        commitment_in_shares: None,
-                        capital: Some(1_000),
-                        date: None,
-                    },
-                ],
-                to: Some(Addr::unchecked("destination")),
-                memo: Some(String::from("note")),
-            },
+            env,
+            mock_info("sub_1", &coins(400, "investment_coin")),
+            claim(-1_000, 100_000),
         )
         .unwrap();
 
-        assert_eq!(3, res.messages.len());
-
-        // verify memo
-        assert_eq!(1, res.attributes.len());
-        let attribute = res.attributes.get(0).unwrap();
-        assert_eq!("memo", attribute.key);
-        assert_eq!("note", attribute.value);
+        // verify only the matured portion is settled
+        let coin = burn_args(msg_at_index(&res, 1));
+        assert_eq!(400, coin.amount.u128());
+        let (_, sent) = send_args(msg_at_index(&res, 2));
+        assert_eq!(40_000, sent.first().unwrap().amount.u128());
 
-        // verify deposit investment
-        let (to_address, coins) = send_args(msg_at_index(&res, 0));
-        let coin = coins.first().unwrap();
-        assert_eq!("tp18vd8fpwxzck93qlwghaj6arh4p7c5n89x8kskz", to_address);
-        assert_eq!("investment_coin", coin.denom);
-        assert_eq!(2_000, coin.amount.u128());
+        // verify the remainder stays outstanding
+        assert_eq!(
+            vec![AssetExchange {
+                investment: Some(-600),
+                commitment_in_shares: None,
+                capital: Some(60_000),
+                date: None,
+                issued_at: None,
+                installments: vec![(1677628800, 600)],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            }],
+            asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
 
-        // verify burn investment
-        let coin = burn_args(msg_at_index(&res, 1));
-        assert_eq!("investment_coin", coin.denom);
-        assert_eq!(2_000, coin.amount.u128());
+        // verify the second installment can't be claimed early
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1675209600); // Feb 01 2023 UTC
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("sub_1", &coins(600, "investment_coin")),
+            claim(-600, 60_000),
+        );
+        assert!(res.is_err());
 
-        // verify send message
-        let (to_address, coins) = send_args(msg_at_index(&res, 2));
-        let coin = coins.first().unwrap();
-        assert_eq!("destination", to_address);
-        assert_eq!("stable_coin", coin.denom);
-        assert_eq!(2_000, coin.amount.u128());
+        // claim the second installment after maturity
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1677715200); // Mar 02 2023 UTC
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("sub_1", &coins(600, "investment_coin")),
+            claim(-600, 60_000),
+        )
+        .unwrap();
 
-        // verify exchange is removed
+        let (_, sent) = send_args(msg_at_index(&res, 2));
+        assert_eq!(60_000, sent.first().unwrap().amount.u128());
         assert_eq!(
             0,
             asset_exchange_storage_read(&deps.storage)
                 .load(Addr::unchecked("sub_1").as_bytes())
                 .unwrap()
                 .len()
-        )
+        );
     }
 
     #[test]
-    fn complete_asset_exchange_without_asset() {
+    fn issue_redemption_with_mismatched_installments() {
         let mut deps = default_deps(None);
-        {
-            asset_exchange_storage(&mut deps.storage)
-                .save(
-                    Addr::unchecked("sub_1").as_bytes(),
-                    &vec![AssetExchange {
-                        investment: Some(-1_000),
-                        commitment_in_shares: None,
-                        capital: Some(1_000),
-                        date: None,
-                    }],
-                )
-                .unwrap();
-        }
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
 
         let res = execute(
             deps.as_mut(),
             mock_env(),
-            mock_info("sub_1", &vec![]),
-            HandleMsg::CompleteAssetExchange {
-                exchanges: vec![AssetExchange {
-                    investment: Some(-1_000),
-                    commitment_in_shares: None,
-                    capital: Some(1_000),
-                    date: None,
-                }],
-                to: Some(Addr::unchecked("destination")),
-                memo: Some(String::from("note")),
-            },
-        );
-
-        assert!(res.is_err());
-    }
-
-    #[test]
-    fn claim_asset_exchange_not_available_yet() {
-        let mut deps = default_deps(None);
-        load_markers(&mut deps.querier);
-        {
-            asset_exchange_storage(&mut deps.storage)
-                .save(
-                    Addr::unchecked("sub_1").as_bytes(),
-                    &vec![AssetExchange {
+            mock_info("gp", &[]),
+            HandleMsg::IssueAssetExchanges {
+                asset_exchanges: vec![IssueAssetExchange {
+                    subscription: Addr::unchecked("sub_1"),
+                    exchange: AssetExchange {
                         investment: Some(-1_000),
                         commitment_in_shares: None,
-                        capital: Some(1_000),
-                        date: Some(ExchangeDate::Available(1675209600)), // Feb 01 2023 UTC
-                    }],
-                )
-                .unwrap();
-        }
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(1672531200); // Jan 01 2023 UTC
-
-        let res = execute(
-            deps.as_mut(),
-            mock_env(),
-            mock_info("sub_1", &coins(1_000, "investment_coin")),
-            HandleMsg::CompleteAssetExchange {
-                exchanges: vec![AssetExchange {
-                    investment: Some(-1_000),
-                    commitment_in_shares: None,
-                    capital: Some(1_000),
-                    date: None,
+                        capital: Some(100_000),
+                        date: None,
+                        issued_at: None,
+                        installments: vec![(1672531200, 400)],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
+                    },
                 }],
-                to: Some(Addr::unchecked("destination")),
-                memo: Some(String::from("note")),
+                idempotency_key: None,
             },
         );
-
         assert!(res.is_err());
     }
 }
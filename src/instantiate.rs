@@ -1,6 +1,8 @@
 use crate::contract::ContractResponse;
+use crate::error::contract_error;
 use crate::msg::InstantiateMsg;
 use crate::state::config;
+use crate::state::genesis_config;
 use crate::state::State;
 use crate::version::CONTRACT_NAME;
 use crate::version::CONTRACT_VERSION;
@@ -11,6 +13,7 @@ use provwasm_std::{
     activate_marker, create_marker, finalize_marker, grant_marker_access, MarkerAccess, MarkerType,
     ProvenanceMsg,
 };
+use std::collections::HashSet;
 
 // Note, you can use StdResult in some functions where you do not
 // make use of the custom errors
@@ -22,6 +25,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> ContractResponse {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    genesis_config(deps.storage).save(&msg)?;
 
     let state = State {
         subscription_code_id: msg.subscription_code_id,
@@ -32,8 +36,44 @@ pub fn instantiate(
         investment_denom: format!("{}.investment", env.contract.address),
         capital_denom: msg.capital_denom,
         capital_per_share: msg.capital_per_share,
+        subscription_instantiate_funds: msg.subscription_instantiate_funds,
+        config_locked: false,
+        redemptions_paused: false,
+        allow_lp_claims: msg.allow_lp_claims,
+        commitment_match_tolerance_bps: msg.commitment_match_tolerance_bps,
+        min_accept_increment: msg.min_accept_increment,
+        accreditation_source: msg.accreditation_source,
+        redemption_rounding: msg.redemption_rounding,
+        mint_commitment_on_accept: msg.mint_commitment_on_accept,
+        max_accepted_subscriptions: msg.max_accepted_subscriptions,
+        min_commitment: msg.min_commitment,
+        max_commitment: msg.max_commitment,
+        proposal_fee: msg.proposal_fee,
+        fee_recipient: msg.fee_recipient,
+        paused_actions: HashSet::new(),
+        min_matching_accreditations: msg.min_matching_accreditations,
+        restrict_redemption_destination: msg.restrict_redemption_destination,
+        closed: false,
+        accreditation_groups: msg.accreditation_groups,
+        paused: false,
     };
 
+    state.ensure_distinct_denoms()?;
+    if state.capital_per_share == 0 {
+        return contract_error("capital_per_share must be non-zero");
+    }
+    if state
+        .accreditation_groups
+        .iter()
+        .any(|group| group.is_empty())
+    {
+        return contract_error("accreditation groups must not be empty");
+    }
+    if let (Some(min), Some(max)) = (state.min_commitment, state.max_commitment) {
+        if min > max {
+            return contract_error("min_commitment must not exceed max_commitment");
+        }
+    }
     config(deps.storage).save(&state)?;
 
     let create_and_activate_marker = |denom: String| -> StdResult<Vec<CosmosMsg<ProvenanceMsg>>> {
@@ -61,12 +101,13 @@ pub fn instantiate(
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
-
     use super::*;
+    use crate::contract::execute;
     use crate::mock::marker_msg;
     use crate::mock::msg_at_index;
+    use crate::msg::HandleMsg;
     use crate::msg::QueryMsg;
+    use crate::msg::RaiseConfig;
     use crate::msg::RaiseState;
     use crate::query::query;
     use cosmwasm_std::coin;
@@ -76,25 +117,38 @@ mod tests {
     use provwasm_mocks::mock_dependencies;
     use provwasm_std::MarkerMsgParams;
 
+    fn instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            subscription_code_id: 0,
+            recovery_admin: Addr::unchecked("marketpalace"),
+            acceptable_accreditations: HashSet::new(),
+            capital_denom: String::from("stable_coin"),
+            capital_per_share: 100,
+            subscription_instantiate_funds: vec![],
+            allow_lp_claims: false,
+            commitment_match_tolerance_bps: None,
+            min_accept_increment: None,
+            accreditation_source: None,
+            redemption_rounding: None,
+            mint_commitment_on_accept: false,
+            max_accepted_subscriptions: None,
+            min_commitment: None,
+            max_commitment: None,
+            proposal_fee: None,
+            fee_recipient: None,
+            min_matching_accreditations: 0,
+            restrict_redemption_destination: false,
+            accreditation_groups: vec![],
+        }
+    }
+
     #[test]
     fn initialization() {
         let mut deps = mock_dependencies(&[]);
         let info = mock_info("gp", &[]);
 
         // instantiate and verify we have 3 messages (create, grant, & activate)
-        let res = instantiate(
-            deps.as_mut(),
-            mock_env(),
-            info,
-            InstantiateMsg {
-                subscription_code_id: 0,
-                recovery_admin: Addr::unchecked("marketpalace"),
-                acceptable_accreditations: HashSet::new(),
-                capital_denom: String::from("stable_coin"),
-                capital_per_share: 100,
-            },
-        )
-        .unwrap();
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
 
         // verify that 8 messages are sent to configure 2 new markers
         assert_eq!(8, res.messages.len());
@@ -199,4 +253,123 @@ mod tests {
         assert_eq!("stable_coin", state.general.capital_denom);
         assert_eq!(100, state.general.capital_per_share);
     }
+
+    #[test]
+    fn initialization_with_colliding_denoms() {
+        let instantiate_with_capital_denom = |capital_denom: String| {
+            instantiate(
+                mock_dependencies(&[]).as_mut(),
+                mock_env(),
+                mock_info("gp", &[]),
+                InstantiateMsg {
+                    capital_denom,
+                    ..instantiate_msg()
+                },
+            )
+        };
+
+        // verify capital can't share a marker with commitment or investment
+        assert!(
+            instantiate_with_capital_denom(format!("{}.commitment", MOCK_CONTRACT_ADDR)).is_err()
+        );
+        assert!(
+            instantiate_with_capital_denom(format!("{}.investment", MOCK_CONTRACT_ADDR)).is_err()
+        );
+        assert!(instantiate_with_capital_denom(String::new()).is_err());
+    }
+
+    #[test]
+    fn initialization_with_zero_capital_per_share() {
+        let res = instantiate(
+            mock_dependencies(&[]).as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            InstantiateMsg {
+                capital_per_share: 0,
+                ..instantiate_msg()
+            },
+        );
+        assert_eq!(
+            "Generic error: capital_per_share must be non-zero",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn initialization_with_min_commitment_above_max() {
+        let res = instantiate(
+            mock_dependencies(&[]).as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            InstantiateMsg {
+                min_commitment: Some(200),
+                max_commitment: Some(100),
+                ..instantiate_msg()
+            },
+        );
+        assert_eq!(
+            "Generic error: min_commitment must not exceed max_commitment",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn config_matches_instantiation() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: RaiseConfig = from_binary(&res).unwrap();
+
+        // verify the economic parameters set at instantiation
+        assert_eq!(
+            RaiseConfig {
+                gp: Addr::unchecked("gp"),
+                recovery_admin: Addr::unchecked("marketpalace"),
+                commitment_denom: format!("{}.commitment", MOCK_CONTRACT_ADDR),
+                investment_denom: format!("{}.investment", MOCK_CONTRACT_ADDR),
+                capital_denom: String::from("stable_coin"),
+                capital_per_share: 100,
+            },
+            config
+        );
+    }
+
+    #[test]
+    fn genesis_config_unchanged_by_updates() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: vec![String::from("506c")].into_iter().collect(),
+                start_after: None,
+            },
+        )
+        .unwrap();
+
+        // verify the live config changed but the genesis config did not
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
+        let state: RaiseState = from_binary(&res).unwrap();
+        assert_eq!(1, state.general.acceptable_accreditations.len());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGenesisConfig {}).unwrap();
+        let genesis: Option<InstantiateMsg> = from_binary(&res).unwrap();
+        assert_eq!(Some(instantiate_msg()), genesis);
+    }
 }
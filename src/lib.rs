@@ -1,3 +1,5 @@
+pub mod accounting;
+pub mod accreditation_msg;
 pub mod contract;
 pub mod error;
 pub mod exchange_asset;
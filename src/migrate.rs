@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::contract::ContractResponse;
+use crate::error::contract_error;
 use crate::msg::MigrateMsg;
 use crate::state::accepted_subscriptions;
 use crate::state::asset_exchange_storage;
@@ -17,8 +18,10 @@ use cosmwasm_std::BankMsg;
 use cosmwasm_std::DepsMut;
 use cosmwasm_std::Env;
 use cosmwasm_std::Response;
+use cosmwasm_std::StdError;
+use cosmwasm_std::StdResult;
 use cosmwasm_storage::singleton_read;
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use provwasm_std::burn_marker_supply;
 use provwasm_std::ProvenanceQuerier;
 use provwasm_std::ProvenanceQuery;
@@ -30,8 +33,54 @@ struct EmptyArgs {}
 
 #[entry_point]
 pub fn migrate(deps: DepsMut<ProvenanceQuery>, env: Env, msg: MigrateMsg) -> ContractResponse {
+    // raises deployed before a contract version was recorded use the 1.x layout
+    let previous = match get_contract_version(deps.storage).ok() {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return contract_error("cannot migrate from a different contract");
+            }
+            parse_version(&stored.version)?
+        }
+        None => (1, 0, 1),
+    };
+    let current = parse_version(CONTRACT_VERSION)?;
+
+    // the same version still falls through so a new subscription code id is applied
+    if previous > current {
+        return contract_error("cannot migrate to an older contract version");
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if previous.0 < 2 {
+        return migrate_from_v1(deps, env, msg);
+    }
+
+    // fields added since 2.0.0, such as commitment bounds, default when the old layout is read
+    let mut state = config(deps.storage).load()?;
+    state.subscription_code_id = msg.subscription_code_id;
+    config(deps.storage).save(&state)?;
+
+    Ok(Response::default())
+}
+
+fn parse_version(version: &str) -> StdResult<(u64, u64, u64)> {
+    let parts = version
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| StdError::generic_err(format!("invalid contract version: {}", version)))?;
+
+    match parts[..] {
+        [major, minor, patch] => Ok((major, minor, patch)),
+        _ => Err(StdError::generic_err(format!(
+            "invalid contract version: {}",
+            version
+        ))),
+    }
+}
+
+fn migrate_from_v1(deps: DepsMut<ProvenanceQuery>, env: Env, msg: MigrateMsg) -> ContractResponse {
     let old_state: StateV1_0_1 = singleton_read(deps.storage, CONFIG_KEY).load()?;
 
     let new_state = State {
@@ -43,6 +92,26 @@ pub fn migrate(deps: DepsMut<ProvenanceQuery>, env: Env, msg: MigrateMsg) -> Con
         investment_denom: old_state.investment_denom,
         capital_denom: old_state.capital_denom,
         capital_per_share: old_state.capital_per_share,
+        subscription_instantiate_funds: vec![],
+        config_locked: false,
+        redemptions_paused: false,
+        allow_lp_claims: false,
+        commitment_match_tolerance_bps: None,
+        min_accept_increment: None,
+        accreditation_source: None,
+        redemption_rounding: None,
+        mint_commitment_on_accept: false,
+        max_accepted_subscriptions: None,
+        min_commitment: None,
+        max_commitment: None,
+        proposal_fee: None,
+        fee_recipient: None,
+        paused_actions: HashSet::new(),
+        min_matching_accreditations: 0,
+        restrict_redemption_destination: false,
+        closed: false,
+        accreditation_groups: vec![],
+        paused: false,
     };
     let new_pending_subscriptions = old_state.pending_review_subs;
     let new_accepted_subscriptions = old_state.accepted_subs;
@@ -123,13 +192,91 @@ mod tests {
     use super::*;
     use crate::mock::{burn_args, load_markers, msg_at_index, send_args};
     use crate::msg::{AssetExchange, IssueAssetExchange};
+    use crate::state::config_read;
     use crate::state::tests::asset_exchange_storage_read;
     use crate::state::{accepted_subscriptions_read, pending_subscriptions_read};
     use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::Storage;
     use cosmwasm_std::{coin, Addr};
-    use cosmwasm_storage::{singleton, singleton_read};
+    use cosmwasm_storage::{singleton, singleton_read, to_length_prefixed};
+    use cw2::get_contract_version;
     use provwasm_mocks::mock_dependencies;
 
+    fn migrate_msg() -> MigrateMsg {
+        MigrateMsg {
+            subscription_code_id: 2,
+            asset_exchanges: vec![],
+        }
+    }
+
+    #[test]
+    fn migration_from_v2_0_0_state() {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "2.0.0").unwrap();
+        // state as written by 2.0.0, before commitment bounds and later config existed
+        deps.storage.set(
+            &to_length_prefixed(CONFIG_KEY),
+            br#"{"subscription_code_id":1,"recovery_admin":"marketpalace","gp":"gp","acceptable_accreditations":["506c"],"commitment_denom":"commitment_coin","investment_denom":"investment_coin","capital_denom":"stable_coin","capital_per_share":100}"#,
+        );
+
+        let res = migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // verify missing fields are defaulted and the new code id is applied
+        let state = config_read(&deps.storage).load().unwrap();
+        assert_eq!(2, state.subscription_code_id);
+        assert_eq!(Addr::unchecked("gp"), state.gp);
+        assert_eq!(None, state.min_commitment);
+        assert_eq!(None, state.max_commitment);
+
+        // verify version is updated
+        assert_eq!(
+            CONTRACT_VERSION,
+            get_contract_version(&deps.storage).unwrap().version
+        );
+    }
+
+    #[test]
+    fn migration_same_version_updates_code_id() {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, CONTRACT_VERSION).unwrap();
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // verify only the code id changed
+        assert_eq!(
+            State {
+                subscription_code_id: 2,
+                ..State::test_default()
+            },
+            config_read(&deps.storage).load().unwrap()
+        );
+        assert_eq!(
+            CONTRACT_VERSION,
+            get_contract_version(&deps.storage).unwrap().version
+        );
+    }
+
+    #[test]
+    fn migration_rejects_downgrade() {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "99.0.0").unwrap();
+
+        assert!(migrate(deps.as_mut(), mock_env(), migrate_msg()).is_err());
+    }
+
+    #[test]
+    fn migration_rejects_different_contract() {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, "other-contract", "1.0.0").unwrap();
+
+        assert!(migrate(deps.as_mut(), mock_env(), migrate_msg()).is_err());
+    }
+
     #[test]
     fn migration() {
         let mut deps = mock_dependencies(&vec![
@@ -164,6 +311,11 @@ mod tests {
                         commitment_in_shares: Some(-1_000),
                         capital: Some(-1_000),
                         date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
                     },
                 }],
             },
@@ -181,6 +333,26 @@ mod tests {
                 investment_denom: String::from("investment_coin"),
                 capital_denom: String::from("stable_coin"),
                 capital_per_share: 100,
+                subscription_instantiate_funds: vec![],
+                config_locked: false,
+                redemptions_paused: false,
+                allow_lp_claims: false,
+                commitment_match_tolerance_bps: None,
+                min_accept_increment: None,
+                accreditation_source: None,
+                redemption_rounding: None,
+                mint_commitment_on_accept: false,
+                max_accepted_subscriptions: None,
+                min_commitment: None,
+                max_commitment: None,
+                proposal_fee: None,
+                fee_recipient: None,
+                paused_actions: HashSet::new(),
+                min_matching_accreditations: 0,
+                restrict_redemption_destination: false,
+                closed: false,
+                accreditation_groups: vec![],
+                paused: false,
             },
             singleton_read(&deps.storage, CONFIG_KEY).load().unwrap()
         );
@@ -2,9 +2,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin, Uint128};
 
-use crate::state::State;
+use crate::state::{RoundingPolicy, State};
+use crate::sub_msg::SubInstantiateMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -13,12 +14,43 @@ pub struct InstantiateMsg {
     pub acceptable_accreditations: HashSet<String>,
     pub capital_denom: String,
     pub capital_per_share: u64,
+    #[serde(default)]
+    pub subscription_instantiate_funds: Vec<Coin>,
+    #[serde(default)]
+    pub allow_lp_claims: bool,
+    #[serde(default)]
+    pub commitment_match_tolerance_bps: Option<u64>,
+    #[serde(default)]
+    pub min_accept_increment: Option<u64>,
+    #[serde(default)]
+    pub accreditation_source: Option<Addr>,
+    #[serde(default)]
+    pub redemption_rounding: Option<RoundingPolicy>,
+    #[serde(default)]
+    pub mint_commitment_on_accept: bool,
+    #[serde(default)]
+    pub max_accepted_subscriptions: Option<u64>,
+    #[serde(default)]
+    pub min_commitment: Option<u64>,
+    #[serde(default)]
+    pub max_commitment: Option<u64>,
+    #[serde(default)]
+    pub proposal_fee: Option<Coin>,
+    #[serde(default)]
+    pub fee_recipient: Option<Addr>,
+    #[serde(default)]
+    pub min_matching_accreditations: u32,
+    #[serde(default)]
+    pub restrict_redemption_destination: bool,
+    #[serde(default)]
+    pub accreditation_groups: Vec<HashSet<String>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct MigrateMsg {
     pub subscription_code_id: u64,
+    #[serde(default)]
     pub asset_exchanges: Vec<IssueAssetExchange>,
 }
 
@@ -28,6 +60,17 @@ pub enum HandleMsg {
     Recover {
         gp: Addr,
     },
+    FreezeSubscription {
+        subscription: Addr,
+        frozen: bool,
+    },
+    ProposeGp {
+        new_gp: Addr,
+    },
+    AcceptGp {},
+    TransferGp {
+        new_gp: Addr,
+    },
     MigrateSubscriptions {
         subscriptions: HashSet<Addr>,
     },
@@ -37,31 +80,155 @@ pub enum HandleMsg {
     CloseSubscriptions {
         subscriptions: HashSet<Addr>,
     },
+    RequeueSubscriptions {
+        subscriptions: HashSet<Addr>,
+    },
+    ReevaluateAllPending {},
+    WithdrawProposal {},
+    RepairMembership {},
+    UpdateAccreditations {
+        accreditations: HashSet<String>,
+        #[serde(default)]
+        start_after: Option<Addr>,
+    },
     IssueAssetExchanges {
         asset_exchanges: Vec<IssueAssetExchange>,
+        #[serde(default)]
+        idempotency_key: Option<String>,
+    },
+    IssueCapitalCalls {
+        calls: Vec<CapitalCall>,
     },
     CancelAssetExchanges {
         cancellations: Vec<IssueAssetExchange>,
+        #[serde(default)]
+        idempotency_key: Option<String>,
     },
+    IssueRedemptionsByCommitmentPct {
+        subscriptions: HashSet<Addr>,
+        pct_bps: u64,
+    },
+    IssueProportionalRedemptions {
+        subscriptions: HashSet<Addr>,
+        total_capital: u64,
+    },
+    CancelRedemptionsOlderThan {
+        issued_before_epoch_seconds: u64,
+    },
+    SweepExpiredRedemptions {},
+    EmergencyHalt {},
+    IssueDistributions {
+        distributions: Vec<Distribution>,
+    },
+    ClaimDistribution {},
+    SetAvailabilityForSubscriptions {
+        subscriptions: HashSet<Addr>,
+        available_epoch_seconds: u64,
+    },
+    SetRedemptionNote {
+        subscription: Addr,
+        asset: u64,
+        capital: u64,
+        gp_note: Option<String>,
+    },
+    NoticeCapitalCall {
+        subscriptions: HashSet<Addr>,
+        amount: u64,
+        effective_epoch_seconds: u64,
+        memo: Option<String>,
+    },
+    ReduceCommitment {
+        subscription: Addr,
+        reduce_shares: u64,
+    },
+    EmitStatement {
+        subscription: Addr,
+    },
+    ReemitRedemptionEvents {},
     CompleteAssetExchange {
         exchanges: Vec<AssetExchange>,
         to: Option<Addr>,
         memo: Option<String>,
+        #[serde(default)]
+        subscription: Option<Addr>,
     },
     AcceptSubscriptions {
         subscriptions: Vec<AcceptSubscription>,
     },
+    AcceptWithRedemptionSchedule {
+        subscription: Addr,
+        commitment_in_capital: u64,
+        redemptions: Vec<Redemption>,
+    },
     IssueWithdrawal {
         to: Addr,
         amount: u64,
         memo: Option<String>,
     },
+    SetLockup {
+        subscription: Addr,
+        lockup_end_epoch_seconds: u64,
+    },
+    SetRedemptionCap {
+        subscription: Addr,
+        max_redemption_capital: Option<u64>,
+    },
+    SetTransferRestriction {
+        subscription: Addr,
+        restricted_until_epoch_seconds: u64,
+    },
+    LockConfig {},
+    CloseRaise {},
+    SetPausedActions {
+        actions: HashSet<ActionKind>,
+    },
+    SetPaused {
+        paused: bool,
+    },
+    SetContractPaused {
+        paused: bool,
+    },
+    SetRedemptionsPaused {
+        paused: bool,
+    },
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct AcceptSubscription {
     pub subscription: Addr,
+    #[serde(default)]
     pub commitment_in_capital: u64,
+    #[serde(default)]
+    pub commitment_in_shares: Option<u64>,
+    #[serde(default)]
+    pub wire_reference: Option<String>,
+    #[serde(default)]
+    pub max_commitment_override: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Distribution {
+    pub subscription: Addr,
+    pub capital: u64,
+    pub available_epoch_seconds: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Redemption {
+    pub asset: u64,
+    pub capital: u64,
+    #[serde(default)]
+    pub date: Option<ExchangeDate>,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub expires_epoch_seconds: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CapitalCall {
+    pub subscription: Addr,
+    pub amount: u64,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -87,6 +254,40 @@ pub struct AssetExchange {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub date: Option<ExchangeDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub issued_at: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub installments: Vec<(u64, u64)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub gp_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+impl AssetExchange {
+    // exchanges match on their terms, ignoring bookkeeping fields set by the raise
+    pub fn matches(&self, other: &AssetExchange) -> bool {
+        self.investment == other.investment
+            && self.commitment_in_shares == other.commitment_in_shares
+            && self.capital == other.capital
+            && self.date == other.date
+    }
+
+    pub fn expired_at(&self, now: u64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+
+    // a call issues investment for capital paid in by the sub
+    pub fn is_capital_call(&self) -> bool {
+        self.investment.unwrap_or(0) > 0 && self.capital.unwrap_or(0) < 0
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -97,12 +298,274 @@ pub enum ExchangeDate {
     Available(u64),
 }
 
+impl HandleMsg {
+    // every variant is listed so a new handler has to be classified explicitly,
+    // None is reserved for governance, reporting and the pause controls themselves
+    pub fn action_kind(&self) -> Option<ActionKind> {
+        match self {
+            HandleMsg::ProposeSubscription { .. } | HandleMsg::WithdrawProposal {} => {
+                Some(ActionKind::Propose)
+            }
+            HandleMsg::AcceptSubscriptions { .. }
+            | HandleMsg::AcceptWithRedemptionSchedule { .. }
+            | HandleMsg::RequeueSubscriptions { .. }
+            | HandleMsg::ReevaluateAllPending {} => Some(ActionKind::Accept),
+            HandleMsg::CloseSubscriptions { .. } => Some(ActionKind::Close),
+            HandleMsg::IssueAssetExchanges { .. }
+            | HandleMsg::IssueCapitalCalls { .. }
+            | HandleMsg::IssueRedemptionsByCommitmentPct { .. }
+            | HandleMsg::IssueProportionalRedemptions { .. }
+            | HandleMsg::IssueDistributions { .. }
+            | HandleMsg::IssueWithdrawal { .. }
+            | HandleMsg::NoticeCapitalCall { .. }
+            | HandleMsg::ReduceCommitment { .. }
+            | HandleMsg::SetAvailabilityForSubscriptions { .. }
+            | HandleMsg::SetRedemptionNote { .. } => Some(ActionKind::Issue),
+            HandleMsg::CancelAssetExchanges { .. }
+            | HandleMsg::CancelRedemptionsOlderThan { .. }
+            | HandleMsg::SweepExpiredRedemptions {} => Some(ActionKind::Cancel),
+            HandleMsg::CompleteAssetExchange { .. } | HandleMsg::ClaimDistribution {} => {
+                Some(ActionKind::Claim)
+            }
+            HandleMsg::Recover { .. }
+            | HandleMsg::FreezeSubscription { .. }
+            | HandleMsg::ProposeGp { .. }
+            | HandleMsg::AcceptGp {}
+            | HandleMsg::TransferGp { .. }
+            | HandleMsg::MigrateSubscriptions { .. }
+            | HandleMsg::RepairMembership {}
+            | HandleMsg::UpdateAccreditations { .. }
+            | HandleMsg::EmergencyHalt {}
+            | HandleMsg::EmitStatement { .. }
+            | HandleMsg::ReemitRedemptionEvents {}
+            | HandleMsg::SetLockup { .. }
+            | HandleMsg::SetRedemptionCap { .. }
+            | HandleMsg::SetTransferRestriction { .. }
+            | HandleMsg::LockConfig {}
+            | HandleMsg::CloseRaise {}
+            | HandleMsg::SetPausedActions { .. }
+            | HandleMsg::SetPaused { .. }
+            | HandleMsg::SetContractPaused { .. }
+            | HandleMsg::SetRedemptionsPaused { .. } => None,
+        }
+    }
+
+    // reporting handlers only emit events, so they don't count as activity
+    pub fn mutates_state(&self) -> bool {
+        !matches!(
+            self,
+            HandleMsg::EmitStatement { .. } | HandleMsg::ReemitRedemptionEvents {}
+        )
+    }
+}
+
+impl ActionKind {
+    pub fn all() -> HashSet<ActionKind> {
+        vec![
+            ActionKind::Accept,
+            ActionKind::Close,
+            ActionKind::Issue,
+            ActionKind::Cancel,
+            ActionKind::Claim,
+            ActionKind::Propose,
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+impl ExchangeDate {
+    pub fn epoch_seconds(&self) -> u64 {
+        match self {
+            ExchangeDate::Due(epoch_seconds) => *epoch_seconds,
+            ExchangeDate::Available(epoch_seconds) => *epoch_seconds,
+        }
+    }
+
+    pub fn claimable_at(&self, epoch_seconds: u64) -> bool {
+        match self {
+            ExchangeDate::Due(due) => *due >= epoch_seconds,
+            ExchangeDate::Available(available) => *available <= epoch_seconds,
+        }
+    }
+
+    pub fn seconds_until_available(&self, epoch_seconds: u64) -> u64 {
+        match self {
+            ExchangeDate::Due(_) => 0,
+            ExchangeDate::Available(available) => available.saturating_sub(epoch_seconds),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetState {},
     GetAllAssetExchanges {},
-    GetAssetExchangesForSubscription { subscription: Addr },
+    GetAssetExchangesForSubscription {
+        subscription: Addr,
+    },
+    GetAssetExchanges {
+        subscriptions: Option<HashSet<Addr>>,
+    },
+    GetUncalledAbove {
+        threshold: u64,
+    },
+    GetRequiredAccreditations {},
+    GetOutstandingRedemptionTotals {},
+    GetOutstandingRedemptions {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    GetLiabilities {},
+    GetRaiseTotals {},
+    GetLastActivity {},
+    GetWireReference {
+        subscription: Addr,
+    },
+    GetUniqueLpCount {},
+    GetAssetExchangesSince {
+        subscription: Addr,
+        since_epoch_seconds: i64,
+    },
+    GetAssetExchangesInRange {
+        subscription: Addr,
+        start: u64,
+        end: u64,
+    },
+    GetCapitalCallNotices {
+        subscription: Addr,
+    },
+    GetTransferRestriction {
+        subscription: Addr,
+    },
+    PlanClaims {
+        subscription: Addr,
+        available_funds: u64,
+        now_epoch_seconds: u64,
+    },
+    GetRedemptionsWithCountdown {
+        subscription: Addr,
+        now_epoch_seconds: u64,
+    },
+    GetRedemptions {
+        subscription: Option<Addr>,
+        only_available: bool,
+    },
+    CanPerform {
+        action: ActionKind,
+        sender: Addr,
+    },
+    ExportCapTable {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    GetSubscriptions {
+        status: SubscriptionStatus,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    WhyNotEligible {
+        subscription: Addr,
+    },
+    GetIncompleteSubscriptions {
+        require: Vec<FieldKind>,
+    },
+    PreviewSubscriptionInstantiate {
+        lp: Addr,
+        initial_commitment: Option<u64>,
+    },
+    GetFlatState {},
+    GetConfig {},
+    GetGenesisConfig {},
+    GetSubscriptionForLp {
+        lp: Addr,
+    },
+    GetSubscriptionTerms {
+        subscription: Addr,
+    },
+    ValidateRedemptionPair {
+        asset: u64,
+        capital: u64,
+    },
+    ConvertSharesToCapital {
+        shares: u64,
+    },
+    ConvertCapitalToShares {
+        capital: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionStatus {
+    Pending,
+    Eligible,
+    Accepted,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    WireReference,
+    Lockup,
+    RedemptionCap,
+    TransferRestriction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Accept,
+    Close,
+    Issue,
+    Cancel,
+    Claim,
+    Propose,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IneligibilityReason {
+    Frozen,
+    DifferentRaise { raise: Addr },
+    MissingAccreditation { acceptable: HashSet<String> },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SubscriptionInstantiatePreview {
+    pub msg: SubInstantiateMsg,
+    pub eligible: bool,
+    pub reply_id: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RaiseConfig {
+    pub gp: Addr,
+    pub recovery_admin: Addr,
+    pub commitment_denom: String,
+    pub investment_denom: String,
+    pub capital_denom: String,
+    pub capital_per_share: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct FlatState {
+    pub gp: Addr,
+    pub recovery_admin: Addr,
+    pub commitment_denom: String,
+    pub investment_denom: String,
+    pub capital_denom: String,
+    pub capital_per_share: u64,
+    pub config_locked: bool,
+    pub redemptions_paused: bool,
+    pub allow_lp_claims: bool,
+    pub pending_count: u64,
+    pub eligible_count: u64,
+    pub accepted_count: u64,
+    pub frozen_count: u64,
+    pub outstanding_redemption_asset: Uint128,
+    pub outstanding_redemption_capital: Uint128,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -112,3 +575,95 @@ pub struct RaiseState {
     pub eligible_subscriptions: HashSet<Addr>,
     pub accepted_subscriptions: HashSet<Addr>,
 }
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RequiredAccreditations {
+    pub accreditations: HashSet<String>,
+    pub match_mode: AccreditationMatchMode,
+    pub min_matching: u32,
+    #[serde(default)]
+    pub groups: Vec<HashSet<String>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccreditationMatchMode {
+    None,
+    Any,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RedemptionTotals {
+    #[serde(rename = "sub")]
+    pub subscription: Addr,
+    pub asset: u64,
+    pub capital: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CapTableEntry {
+    #[serde(rename = "sub")]
+    pub subscription: Addr,
+    pub net_shares: i64,
+    pub net_capital: i64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Liabilities {
+    pub redemptions: Uint128,
+    pub distributions: Uint128,
+    pub total: Uint128,
+    pub capital_balance: Uint128,
+    // none when there is nothing owed
+    pub coverage_bps: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RaiseTotals {
+    pub total_committed_shares: Uint128,
+    pub total_invested_shares: Uint128,
+    pub total_outstanding_redemption_capital: Uint128,
+    pub accepted_subscription_count: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UncalledCommitment {
+    #[serde(rename = "sub")]
+    pub subscription: Addr,
+    pub amount: Uint128,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SubscriptionRedemption {
+    #[serde(rename = "sub")]
+    pub subscription: Addr,
+    pub exchange: AssetExchange,
+}
+
+// totals cover every outstanding redemption, not just the page
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OutstandingRedemptions {
+    pub redemptions: Vec<SubscriptionRedemption>,
+    pub total_asset: Uint128,
+    pub total_capital: Uint128,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RedemptionCountdown {
+    pub exchange: AssetExchange,
+    pub seconds_until_available: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ClaimPlan {
+    pub exchanges: Vec<AssetExchange>,
+    pub leftover: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CapitalCallNotice {
+    pub amount: u64,
+    pub effective_epoch_seconds: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
@@ -1,16 +1,40 @@
-use cosmwasm_std::{entry_point, to_binary, Addr, Binary, Deps, Env, StdResult};
+use cosmwasm_std::{entry_point, to_binary, Addr, Binary, Deps, Env, StdError, StdResult, Uint128};
 use provwasm_std::ProvenanceQuery;
 use schemars::JsonSchema;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::convert::{TryFrom, TryInto};
 
-use crate::msg::{AssetExchange, QueryMsg, RaiseState};
+use crate::accounting::remaining_commitment;
+use crate::msg::{
+    AccreditationMatchMode, ActionKind, AssetExchange, CapTableEntry, ClaimPlan, FieldKind,
+    FlatState, IneligibilityReason, Liabilities, OutstandingRedemptions, QueryMsg, RaiseConfig,
+    RaiseState, RaiseTotals, RedemptionCountdown, RedemptionTotals, RequiredAccreditations,
+    SubscriptionRedemption, SubscriptionStatus, UncalledCommitment,
+};
 use crate::state::{
-    accepted_subscriptions_read, asset_exchange_storage_read, config_read,
-    eligible_subscriptions_read, pending_subscriptions_read,
+    accepted_subscriptions_read, asset_exchange_storage_read, called_capital_storage_read,
+    capital_call_notice_storage_read, config_read, eligible_subscriptions_read,
+    frozen_subscriptions_read, genesis_config_read, last_activity_read, lockup_storage_read,
+    lp_to_subscription_read, outstanding_distributions_read, pending_subscriptions_read,
+    redeemed_capital_storage_read, redemption_cap_storage_read, transfer_restriction_storage_read,
+    wire_reference_storage_read,
+};
+use crate::sub_msg::{SubQueryMsg, SubState, SubTerms};
+use crate::subscribe::{
+    attributes, meets_accreditation_requirement, preview_subscription_instantiate,
+    requires_accreditation, resolve_accreditations,
 };
 
+const DEFAULT_CAP_TABLE_LIMIT: u32 = 10;
+const MAX_CAP_TABLE_LIMIT: u32 = 30;
+const MAX_ASSET_EXCHANGE_BATCH_SIZE: usize = 30;
+const DEFAULT_SUBSCRIPTIONS_LIMIT: u32 = 30;
+const MAX_SUBSCRIPTIONS_LIMIT: u32 = 100;
+const MAX_RAISE_TOTALS_SUBSCRIPTIONS: usize = 100;
+
 #[entry_point]
-pub fn query(deps: Deps<ProvenanceQuery>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<ProvenanceQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetState {} => to_binary(&RaiseState {
             general: config_read(deps.storage).load()?,
@@ -44,10 +68,744 @@ pub fn query(deps: Deps<ProvenanceQuery>, _env: Env, msg: QueryMsg) -> StdResult
         QueryMsg::GetAssetExchangesForSubscription { subscription } => {
             to_binary(&asset_exchange_storage_read(deps.storage).may_load(subscription.as_bytes())?)
         }
+        QueryMsg::GetAssetExchanges { subscriptions } => {
+            let mut subscriptions: Vec<Addr> = match subscriptions {
+                Some(subscriptions) => {
+                    if subscriptions.len() > MAX_ASSET_EXCHANGE_BATCH_SIZE {
+                        return Err(StdError::generic_err(format!(
+                            "cannot query more than {} subscriptions",
+                            MAX_ASSET_EXCHANGE_BATCH_SIZE
+                        )));
+                    }
+                    subscriptions.into_iter().collect()
+                }
+                None => accepted_subscriptions_read(deps.storage)
+                    .may_load()?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect(),
+            };
+            subscriptions.sort();
+
+            // subs without stored exchanges are omitted rather than returned empty
+            let mut batch = vec![];
+            for subscription in subscriptions.into_iter() {
+                if batch.len() == MAX_ASSET_EXCHANGE_BATCH_SIZE {
+                    break;
+                }
+                if let Some(exchanges) =
+                    asset_exchange_storage_read(deps.storage).may_load(subscription.as_bytes())?
+                {
+                    batch.push(SubscriptionAssetExchanges {
+                        subscription,
+                        exchanges,
+                    });
+                }
+            }
+
+            to_binary(&batch)
+        }
+        QueryMsg::GetUncalledAbove { threshold } => {
+            let state = config_read(deps.storage).load()?;
+
+            let mut uncalled = vec![];
+            for subscription in accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+            {
+                let amount = deps
+                    .querier
+                    .query_balance(subscription.as_str(), state.commitment_denom.clone())?
+                    .amount;
+                if amount.u128() > threshold.into() {
+                    uncalled.push(UncalledCommitment {
+                        subscription,
+                        amount,
+                    });
+                }
+            }
+
+            to_binary(&uncalled)
+        }
+        QueryMsg::GetRequiredAccreditations {} => {
+            let state = config_read(deps.storage).load()?;
+            let accreditations = resolve_accreditations(deps, &state)?;
+            let (match_mode, min_matching) = if !requires_accreditation(&state, &accreditations) {
+                (AccreditationMatchMode::None, 0)
+            } else {
+                (
+                    AccreditationMatchMode::Any,
+                    state.min_matching_accreditations.max(1),
+                )
+            };
+
+            to_binary(&RequiredAccreditations {
+                accreditations,
+                match_mode,
+                min_matching,
+                groups: state.accreditation_groups,
+            })
+        }
+        QueryMsg::GetOutstandingRedemptionTotals {} => {
+            let mut totals = vec![];
+            for subscription in accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+            {
+                let redemptions: Vec<AssetExchange> = asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|e| e.investment.unwrap_or(0) < 0)
+                    .collect();
+                if redemptions.is_empty() {
+                    continue;
+                }
+
+                totals.push(RedemptionTotals {
+                    subscription,
+                    asset: redemptions
+                        .iter()
+                        .filter_map(|e| e.investment)
+                        .map(|investment| investment.unsigned_abs())
+                        .sum(),
+                    capital: redemptions
+                        .iter()
+                        .filter_map(|e| e.capital)
+                        .map(|capital| capital.unsigned_abs())
+                        .sum(),
+                });
+            }
+
+            to_binary(&totals)
+        }
+        QueryMsg::GetOutstandingRedemptions { start_after, limit } => {
+            let limit = limit
+                .unwrap_or(DEFAULT_SUBSCRIPTIONS_LIMIT)
+                .min(MAX_SUBSCRIPTIONS_LIMIT) as usize;
+            let mut subscriptions: Vec<Addr> = accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            subscriptions.sort();
+
+            // pages are whole subscriptions so start_after always resumes cleanly
+            let mut outstanding = OutstandingRedemptions {
+                redemptions: vec![],
+                total_asset: Uint128::zero(),
+                total_capital: Uint128::zero(),
+            };
+            let mut paged = 0;
+            for subscription in subscriptions {
+                let mut redemptions: Vec<AssetExchange> = asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|e| e.investment.unwrap_or(0) < 0)
+                    .collect();
+                if redemptions.is_empty() {
+                    continue;
+                }
+
+                for redemption in redemptions.iter() {
+                    outstanding.total_asset +=
+                        Uint128::from(redemption.investment.unwrap_or(0).unsigned_abs());
+                    outstanding.total_capital +=
+                        Uint128::from(redemption.capital.unwrap_or(0).unsigned_abs());
+                }
+
+                let after_start = match &start_after {
+                    Some(start_after) => &subscription > start_after,
+                    None => true,
+                };
+                if after_start && paged < limit {
+                    redemptions.sort_by_key(|e| e.investment.unwrap_or(0).unsigned_abs());
+                    outstanding
+                        .redemptions
+                        .extend(
+                            redemptions
+                                .into_iter()
+                                .map(|exchange| SubscriptionRedemption {
+                                    subscription: subscription.clone(),
+                                    exchange,
+                                }),
+                        );
+                    paged += 1;
+                }
+            }
+
+            to_binary(&outstanding)
+        }
+        QueryMsg::GetLiabilities {} => {
+            let state = config_read(deps.storage).load()?;
+
+            // capital owed out of the contract on exchanges not yet claimed
+            let mut redemptions: u128 = 0;
+            let mut distributions: u128 = 0;
+            for subscription in accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+            {
+                for exchange in asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                {
+                    let capital = exchange.capital.unwrap_or(0);
+                    if capital <= 0 {
+                        continue;
+                    }
+                    if exchange.investment.unwrap_or(0) < 0 {
+                        redemptions += capital as u128;
+                    } else {
+                        distributions += capital as u128;
+                    }
+                }
+
+                distributions += outstanding_distributions_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|d| d.capital as u128)
+                    .sum::<u128>();
+            }
+
+            let total = redemptions + distributions;
+            let capital_balance = deps
+                .querier
+                .query_balance(env.contract.address, state.capital_denom)?
+                .amount;
+            let scaled_balance = capital_balance
+                .u128()
+                .checked_mul(10_000)
+                .ok_or_else(|| StdError::generic_err("liability coverage overflow"))?;
+
+            to_binary(&Liabilities {
+                redemptions: Uint128::new(redemptions),
+                distributions: Uint128::new(distributions),
+                total: Uint128::new(total),
+                capital_balance,
+                coverage_bps: scaled_balance.checked_div(total).map(|bps| bps as u64),
+            })
+        }
+        QueryMsg::GetRaiseTotals {} => {
+            let state = config_read(deps.storage).load()?;
+            let accepted = accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default();
+
+            // each subscription costs balance queries, so large raises must paginate instead
+            if accepted.len() > MAX_RAISE_TOTALS_SUBSCRIPTIONS {
+                return Err(StdError::generic_err(format!(
+                    "cannot total more than {} subscriptions, use ExportCapTable pagination",
+                    MAX_RAISE_TOTALS_SUBSCRIPTIONS
+                )));
+            }
+
+            let mut committed: u128 = 0;
+            let mut invested: u128 = 0;
+            let mut redemption_capital: u128 = 0;
+            for subscription in &accepted {
+                committed += u128::from(remaining_commitment(deps, subscription)?);
+                invested += deps
+                    .querier
+                    .query_balance(subscription.as_str(), state.investment_denom.clone())?
+                    .amount
+                    .u128();
+                redemption_capital += asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|e| e.investment.unwrap_or(0) < 0)
+                    .filter_map(|e| e.capital)
+                    .map(|capital| u128::from(capital.unsigned_abs()))
+                    .sum::<u128>();
+            }
+
+            to_binary(&RaiseTotals {
+                total_committed_shares: Uint128::new(committed),
+                total_invested_shares: Uint128::new(invested),
+                total_outstanding_redemption_capital: Uint128::new(redemption_capital),
+                accepted_subscription_count: accepted.len() as u64,
+            })
+        }
+        QueryMsg::GetLastActivity {} => to_binary(&last_activity_read(deps.storage).may_load()?),
+        QueryMsg::GetWireReference { subscription } => {
+            to_binary(&wire_reference_storage_read(deps.storage).may_load(subscription.as_bytes())?)
+        }
+        QueryMsg::GetAssetExchangesSince {
+            subscription,
+            since_epoch_seconds,
+        } => {
+            // undated exchanges can't be placed in a period, so they're left out
+            let exchanges: Vec<AssetExchange> = asset_exchange_storage_read(deps.storage)
+                .may_load(subscription.as_bytes())?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| match &e.date {
+                    Some(date) => {
+                        i128::from(date.epoch_seconds()) >= i128::from(since_epoch_seconds)
+                    }
+                    None => false,
+                })
+                .collect();
+
+            to_binary(&exchanges)
+        }
+        QueryMsg::GetAssetExchangesInRange {
+            subscription,
+            start,
+            end,
+        } => {
+            if start > end {
+                return Err(StdError::generic_err("range start must not be after end"));
+            }
+
+            // the window is inclusive and undated exchanges are left out
+            let exchanges: Vec<AssetExchange> = asset_exchange_storage_read(deps.storage)
+                .may_load(subscription.as_bytes())?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| match &e.date {
+                    Some(date) => (start..=end).contains(&date.epoch_seconds()),
+                    None => false,
+                })
+                .collect();
+
+            to_binary(&exchanges)
+        }
+        QueryMsg::GetCapitalCallNotices { subscription } => to_binary(
+            &capital_call_notice_storage_read(deps.storage)
+                .may_load(subscription.as_bytes())?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::GetTransferRestriction { subscription } => to_binary(
+            &transfer_restriction_storage_read(deps.storage).may_load(subscription.as_bytes())?,
+        ),
+        QueryMsg::PlanClaims {
+            subscription,
+            available_funds,
+            now_epoch_seconds,
+        } => {
+            let mut plan = ClaimPlan {
+                exchanges: vec![],
+                leftover: available_funds,
+            };
+
+            // claims are planned in issue order, stopping at the first that can't be funded
+            for exchange in asset_exchange_storage_read(deps.storage)
+                .may_load(subscription.as_bytes())?
+                .unwrap_or_default()
+            {
+                let investment = exchange.investment.unwrap_or(0);
+                if investment >= 0 {
+                    continue;
+                }
+                if let Some(date) = &exchange.date {
+                    if !date.claimable_at(now_epoch_seconds) {
+                        continue;
+                    }
+                }
+
+                if investment.unsigned_abs() > plan.leftover {
+                    break;
+                }
+                plan.leftover -= investment.unsigned_abs();
+                plan.exchanges.push(exchange);
+            }
+
+            to_binary(&plan)
+        }
+        QueryMsg::GetRedemptionsWithCountdown {
+            subscription,
+            now_epoch_seconds,
+        } => {
+            let countdowns: Vec<RedemptionCountdown> = asset_exchange_storage_read(deps.storage)
+                .may_load(subscription.as_bytes())?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|exchange| exchange.investment.unwrap_or(0) < 0)
+                .map(|exchange| RedemptionCountdown {
+                    seconds_until_available: exchange
+                        .date
+                        .as_ref()
+                        .map(|date| date.seconds_until_available(now_epoch_seconds))
+                        .unwrap_or(0),
+                    exchange,
+                })
+                .collect();
+
+            to_binary(&countdowns)
+        }
+        QueryMsg::GetRedemptions {
+            subscription,
+            only_available,
+        } => {
+            let subscriptions: Vec<Addr> = match subscription {
+                Some(subscription) => vec![subscription],
+                None => {
+                    let mut accepted: Vec<Addr> = accepted_subscriptions_read(deps.storage)
+                        .may_load()?
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect();
+                    accepted.sort();
+                    accepted
+                }
+            };
+
+            let now = env.block.time.seconds();
+            let mut redemptions = vec![];
+            for subscription in subscriptions {
+                for exchange in asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                {
+                    if exchange.investment.unwrap_or(0) >= 0 {
+                        continue;
+                    }
+                    if only_available {
+                        if let Some(date) = &exchange.date {
+                            if !date.claimable_at(now) {
+                                continue;
+                            }
+                        }
+                    }
+
+                    redemptions.push(SubscriptionRedemption {
+                        subscription: subscription.clone(),
+                        exchange,
+                    });
+                }
+            }
+            // undated redemptions are available immediately
+            redemptions.sort_by_key(|redemption| {
+                redemption
+                    .exchange
+                    .date
+                    .as_ref()
+                    .map(|date| date.epoch_seconds())
+                    .unwrap_or(0)
+            });
+
+            to_binary(&redemptions)
+        }
+        QueryMsg::CanPerform { action, sender } => {
+            let state = config_read(deps.storage).load()?;
+
+            let allowed = !state.paused
+                && !state.paused_actions.contains(&action)
+                && match action {
+                    ActionKind::Accept
+                    | ActionKind::Close
+                    | ActionKind::Issue
+                    | ActionKind::Cancel => sender == state.gp,
+                    ActionKind::Claim => {
+                        let frozen = frozen_subscriptions_read(deps.storage)
+                            .may_load()?
+                            .unwrap_or_default()
+                            .contains(&sender);
+                        let has_exchanges = !asset_exchange_storage_read(deps.storage)
+                            .may_load(sender.as_bytes())?
+                            .unwrap_or_default()
+                            .is_empty();
+
+                        has_exchanges && !frozen && !state.redemptions_paused
+                    }
+                    ActionKind::Propose => !state.closed,
+                };
+
+            to_binary(&allowed)
+        }
+        QueryMsg::GetUniqueLpCount {} => {
+            // one smart query per accepted subscription, so cost grows with the raise
+            let mut lps = HashSet::new();
+            for subscription in accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+            {
+                let sub_state: SubState = deps
+                    .querier
+                    .query_wasm_smart(subscription, &SubQueryMsg::GetState {})?;
+                lps.insert(sub_state.lp);
+            }
+
+            to_binary(&(lps.len() as u64))
+        }
+        QueryMsg::ExportCapTable { start_after, limit } => {
+            let limit = limit
+                .unwrap_or(DEFAULT_CAP_TABLE_LIMIT)
+                .min(MAX_CAP_TABLE_LIMIT) as usize;
+            let mut subscriptions: Vec<Addr> = accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|subscription| match &start_after {
+                    Some(start_after) => subscription > start_after,
+                    None => true,
+                })
+                .collect();
+            subscriptions.sort();
+
+            let state = config_read(deps.storage).load()?;
+            let overflow = |_| StdError::generic_err("cap table entry overflow");
+            let mut entries = vec![];
+            for subscription in subscriptions.into_iter().take(limit) {
+                let exchanges = asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default();
+
+                // settled exchanges leave the queue, so pending ones are added to what is held
+                let held = deps
+                    .querier
+                    .query_balance(subscription.as_str(), state.investment_denom.clone())?
+                    .amount
+                    .u128();
+                let pending_shares: i128 = exchanges
+                    .iter()
+                    .filter_map(|e| e.investment)
+                    .map(i128::from)
+                    .sum();
+                let net_shares = i128::try_from(held).map_err(overflow)? + pending_shares;
+
+                // calls are counted when issued, so only other pending capital is added
+                let called = called_capital_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default();
+                let redeemed = redeemed_capital_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default();
+                let pending_capital: i128 = exchanges
+                    .iter()
+                    .filter(|e| !e.is_capital_call())
+                    .filter_map(|e| e.capital)
+                    .map(i128::from)
+                    .sum();
+                let net_capital = i128::from(redeemed) - i128::from(called) + pending_capital;
+
+                entries.push(CapTableEntry {
+                    subscription,
+                    net_shares: net_shares.try_into().map_err(overflow)?,
+                    net_capital: net_capital.try_into().map_err(overflow)?,
+                });
+            }
+
+            to_binary(&entries)
+        }
+        QueryMsg::GetSubscriptions {
+            status,
+            start_after,
+            limit,
+        } => {
+            let limit = limit
+                .unwrap_or(DEFAULT_SUBSCRIPTIONS_LIMIT)
+                .min(MAX_SUBSCRIPTIONS_LIMIT) as usize;
+            let subscriptions = match status {
+                SubscriptionStatus::Pending => pending_subscriptions_read(deps.storage),
+                SubscriptionStatus::Eligible => eligible_subscriptions_read(deps.storage),
+                SubscriptionStatus::Accepted => accepted_subscriptions_read(deps.storage),
+            }
+            .may_load()?
+            .unwrap_or_default();
+
+            let mut page: Vec<Addr> = subscriptions
+                .into_iter()
+                .filter(|subscription| match &start_after {
+                    Some(start_after) => subscription > start_after,
+                    None => true,
+                })
+                .collect();
+            page.sort();
+            page.truncate(limit);
+
+            to_binary(&page)
+        }
+        QueryMsg::WhyNotEligible { subscription } => {
+            let state = config_read(deps.storage).load()?;
+            let mut reasons = vec![];
+
+            if frozen_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+                .contains(&subscription)
+            {
+                reasons.push(IneligibilityReason::Frozen);
+            }
+
+            let sub_state: SubState = deps
+                .querier
+                .query_wasm_smart(subscription, &SubQueryMsg::GetState {})?;
+
+            if sub_state.raise != env.contract.address {
+                reasons.push(IneligibilityReason::DifferentRaise {
+                    raise: sub_state.raise.clone(),
+                });
+            }
+
+            let acceptable = resolve_accreditations(deps, &state)?;
+            if !meets_accreditation_requirement(
+                &state,
+                &acceptable,
+                &attributes(deps, &sub_state.lp),
+            ) {
+                reasons.push(IneligibilityReason::MissingAccreditation { acceptable });
+            }
+
+            to_binary(&reasons)
+        }
+        QueryMsg::GetIncompleteSubscriptions { require } => {
+            let mut incomplete = vec![];
+            for subscription in accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+            {
+                let key = subscription.as_bytes();
+                let mut missing = false;
+                for field in require.iter() {
+                    let present = match field {
+                        FieldKind::WireReference => wire_reference_storage_read(deps.storage)
+                            .may_load(key)?
+                            .is_some(),
+                        FieldKind::Lockup => {
+                            lockup_storage_read(deps.storage).may_load(key)?.is_some()
+                        }
+                        FieldKind::RedemptionCap => redemption_cap_storage_read(deps.storage)
+                            .may_load(key)?
+                            .is_some(),
+                        FieldKind::TransferRestriction => {
+                            transfer_restriction_storage_read(deps.storage)
+                                .may_load(key)?
+                                .is_some()
+                        }
+                    };
+                    missing |= !present;
+                }
+
+                if missing {
+                    incomplete.push(subscription);
+                }
+            }
+            incomplete.sort();
+
+            to_binary(&incomplete)
+        }
+        QueryMsg::PreviewSubscriptionInstantiate {
+            lp,
+            initial_commitment,
+        } => to_binary(&preview_subscription_instantiate(
+            deps,
+            &config_read(deps.storage).load()?,
+            lp,
+            initial_commitment,
+        )?),
+        QueryMsg::GetGenesisConfig {} => to_binary(&genesis_config_read(deps.storage).may_load()?),
+        QueryMsg::GetSubscriptionTerms { subscription } => {
+            let terms: SubTerms = deps
+                .querier
+                .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetTerms {})
+                .map_err(|_| {
+                    StdError::generic_err(format!(
+                        "subscription {} did not return its terms",
+                        subscription
+                    ))
+                })?;
+
+            to_binary(&terms)
+        }
+        QueryMsg::GetSubscriptionForLp { lp } => {
+            let mut subscriptions: Vec<Addr> = lp_to_subscription_read(deps.storage)
+                .may_load(lp.as_bytes())?
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            subscriptions.sort();
+
+            to_binary(&subscriptions)
+        }
+        QueryMsg::GetConfig {} => {
+            let state = config_read(deps.storage).load()?;
+
+            to_binary(&RaiseConfig {
+                gp: state.gp,
+                recovery_admin: state.recovery_admin,
+                commitment_denom: state.commitment_denom,
+                investment_denom: state.investment_denom,
+                capital_denom: state.capital_denom,
+                capital_per_share: state.capital_per_share,
+            })
+        }
+        QueryMsg::GetFlatState {} => {
+            let state = config_read(deps.storage).load()?;
+            let accepted = accepted_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default();
+
+            let mut outstanding_redemption_asset: u128 = 0;
+            let mut outstanding_redemption_capital: u128 = 0;
+            for subscription in accepted.iter() {
+                for redemption in asset_exchange_storage_read(deps.storage)
+                    .may_load(subscription.as_bytes())?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|e| e.investment.unwrap_or(0) < 0)
+                {
+                    outstanding_redemption_asset +=
+                        u128::from(redemption.investment.unwrap_or(0).unsigned_abs());
+                    outstanding_redemption_capital +=
+                        u128::from(redemption.capital.unwrap_or(0).unsigned_abs());
+                }
+            }
+
+            to_binary(&FlatState {
+                gp: state.gp,
+                recovery_admin: state.recovery_admin,
+                commitment_denom: state.commitment_denom,
+                investment_denom: state.investment_denom,
+                capital_denom: state.capital_denom,
+                capital_per_share: state.capital_per_share,
+                config_locked: state.config_locked,
+                redemptions_paused: state.redemptions_paused,
+                allow_lp_claims: state.allow_lp_claims,
+                pending_count: pending_subscriptions_read(deps.storage)
+                    .may_load()?
+                    .unwrap_or_default()
+                    .len() as u64,
+                eligible_count: eligible_subscriptions_read(deps.storage)
+                    .may_load()?
+                    .unwrap_or_default()
+                    .len() as u64,
+                accepted_count: accepted.len() as u64,
+                frozen_count: frozen_subscriptions_read(deps.storage)
+                    .may_load()?
+                    .unwrap_or_default()
+                    .len() as u64,
+                outstanding_redemption_asset: Uint128::new(outstanding_redemption_asset),
+                outstanding_redemption_capital: Uint128::new(outstanding_redemption_capital),
+            })
+        }
+        QueryMsg::ValidateRedemptionPair { asset, capital } => {
+            let state = config_read(deps.storage).load()?;
+
+            to_binary(
+                &(!state.not_evenly_divisble(capital)
+                    && asset == state.capital_to_shares(capital)?),
+            )
+        }
+        QueryMsg::ConvertSharesToCapital { shares } => {
+            let state = config_read(deps.storage).load()?;
+
+            to_binary(&state.shares_to_capital(shares)?)
+        }
+        QueryMsg::ConvertCapitalToShares { capital } => {
+            let state = config_read(deps.storage).load()?;
+
+            // capital that doesn't divide into whole shares is rounded down
+            to_binary(&state.capital_to_shares(capital)?)
+        }
     }
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 struct SubscriptionAssetExchanges {
     #[serde(rename = "sub")]
     subscription: Addr,
@@ -58,11 +816,20 @@ struct SubscriptionAssetExchanges {
 mod tests {
     use super::*;
 
+    use crate::mock::wasm_smart_mock_dependencies;
+    use crate::subscribe::tests::mock_sub_state;
     use crate::{
+        contract::{execute, tests::default_deps},
+        msg::{AcceptSubscription, CapitalCallNotice, Distribution, ExchangeDate, HandleMsg},
         query::query,
-        state::{asset_exchange_storage, tests::set_accepted},
+        state::{
+            asset_exchange_storage, called_capital_storage, config, outstanding_distributions,
+            tests::{set_accepted, set_eligible},
+            wire_reference_storage, LastActivity, State,
+        },
     };
-    use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coin, coins, from_binary, ContractResult, SystemError, SystemResult};
     use provwasm_mocks::mock_dependencies;
 
     #[test]
@@ -78,6 +845,11 @@ mod tests {
                         commitment_in_shares: Some(1_000),
                         capital: None,
                         date: None,
+                        issued_at: None,
+                        installments: vec![],
+                        gp_note: None,
+                        memo: None,
+                        expires_at: None,
                     }],
                 )
                 .unwrap();
@@ -98,6 +870,11 @@ mod tests {
                     commitment_in_shares: Some(1_000),
                     capital: None,
                     date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
                 }],
             )
             .unwrap();
@@ -105,4 +882,1193 @@ mod tests {
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAllAssetExchanges {}).unwrap();
         println!("{}", std::str::from_utf8(res.as_slice()).unwrap());
     }
+
+    #[test]
+    fn get_asset_exchanges() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+        let exchange = AssetExchange {
+            investment: None,
+            commitment_in_shares: Some(1_000),
+            capital: None,
+            date: None,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        for subscription in ["sub_1", "sub_3"] {
+            asset_exchange_storage(&mut deps.storage)
+                .save(
+                    Addr::unchecked(subscription).as_bytes(),
+                    &vec![exchange.clone()],
+                )
+                .unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAssetExchanges {
+                subscriptions: Some(
+                    vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_2")]
+                        .into_iter()
+                        .collect(),
+                ),
+            },
+        )
+        .unwrap();
+        let batch: Vec<SubscriptionAssetExchanges> = from_binary(&res).unwrap();
+
+        // verify subs without exchanges are omitted
+        assert_eq!(
+            vec![SubscriptionAssetExchanges {
+                subscription: Addr::unchecked("sub_1"),
+                exchanges: vec![exchange.clone()],
+            }],
+            batch
+        );
+
+        // verify all accepted subs are returned by default
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAssetExchanges {
+                subscriptions: None,
+            },
+        )
+        .unwrap();
+        let batch: Vec<SubscriptionAssetExchanges> = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_3")],
+            batch
+                .into_iter()
+                .map(|entry| entry.subscription)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn get_uncalled_above() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(5_000, "commitment_coin"));
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_2"), coins(500, "commitment_coin"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetUncalledAbove { threshold: 1_000 },
+        )
+        .unwrap();
+        let uncalled: Vec<UncalledCommitment> = from_binary(&res).unwrap();
+
+        // verify only the sub above the threshold is returned
+        assert_eq!(1, uncalled.len());
+        assert_eq!("sub_1", uncalled[0].subscription);
+        assert_eq!(5_000, uncalled[0].amount.u128());
+    }
+
+    #[test]
+    fn get_required_accreditations() {
+        let deps = default_deps(None);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRequiredAccreditations {},
+        )
+        .unwrap();
+        let required: RequiredAccreditations = from_binary(&res).unwrap();
+
+        // verify the configured accreditations are returned
+        assert_eq!(
+            vec![String::from("506c")]
+                .into_iter()
+                .collect::<HashSet<String>>(),
+            required.accreditations
+        );
+        assert_eq!(AccreditationMatchMode::Any, required.match_mode);
+    }
+
+    #[test]
+    fn get_outstanding_redemption_totals() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            let redemption = AssetExchange {
+                investment: Some(-1_000),
+                commitment_in_shares: None,
+                capital: Some(1_000),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            };
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![redemption.clone(), redemption.clone()],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_2").as_bytes(),
+                    &vec![
+                        redemption,
+                        AssetExchange {
+                            investment: None,
+                            commitment_in_shares: Some(1_000),
+                            capital: None,
+                            date: None,
+                            issued_at: None,
+                            installments: vec![],
+                            gp_note: None,
+                            memo: None,
+                            expires_at: None,
+                        },
+                    ],
+                )
+                .unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOutstandingRedemptionTotals {},
+        )
+        .unwrap();
+        let totals: Vec<RedemptionTotals> = from_binary(&res).unwrap();
+
+        // verify only subs with redemptions are totaled
+        assert_eq!(2, totals.len());
+        let sub_1 = totals.iter().find(|t| t.subscription == "sub_1").unwrap();
+        assert_eq!(2_000, sub_1.asset);
+        assert_eq!(2_000, sub_1.capital);
+        let sub_2 = totals.iter().find(|t| t.subscription == "sub_2").unwrap();
+        assert_eq!(1_000, sub_2.asset);
+        assert_eq!(1_000, sub_2.capital);
+    }
+
+    #[test]
+    fn get_outstanding_redemptions() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_2", "sub_1", "sub_3"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            let redemption = |asset: i64| AssetExchange {
+                investment: Some(-asset),
+                commitment_in_shares: None,
+                capital: Some(asset * 100),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            };
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![redemption(30), redemption(10), redemption(20)],
+                )
+                .unwrap();
+            storage
+                .save(Addr::unchecked("sub_2").as_bytes(), &vec![redemption(5)])
+                .unwrap();
+        }
+        let page = |start_after: Option<&str>| -> OutstandingRedemptions {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetOutstandingRedemptions {
+                        start_after: start_after.map(Addr::unchecked),
+                        limit: Some(1),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // verify the first page is the first sub ordered by asset
+        let first = page(None);
+        assert_eq!(
+            vec![10, 20, 30],
+            first
+                .redemptions
+                .iter()
+                .map(|r| r.exchange.investment.unwrap().unsigned_abs())
+                .collect::<Vec<u64>>()
+        );
+        assert!(first.redemptions.iter().all(|r| r.subscription == "sub_1"));
+
+        // verify the next page resumes after it and totals always cover every redemption
+        let second = page(Some("sub_1"));
+        assert_eq!(1, second.redemptions.len());
+        assert_eq!("sub_2", second.redemptions[0].subscription);
+        for outstanding in [first, second] {
+            assert_eq!(Uint128::new(65), outstanding.total_asset);
+            assert_eq!(Uint128::new(6_500), outstanding.total_capital);
+        }
+        assert!(page(Some("sub_2")).redemptions.is_empty());
+    }
+
+    #[test]
+    fn get_liabilities() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            let exchange = |investment: i64, capital: i64| AssetExchange {
+                investment: Some(investment),
+                commitment_in_shares: None,
+                capital: Some(capital),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            };
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![exchange(-1_000, 10_000), exchange(1_000, -10_000)],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_2").as_bytes(),
+                    &vec![exchange(0, 6_000)],
+                )
+                .unwrap();
+        }
+        outstanding_distributions(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_2").as_bytes(),
+                &vec![Distribution {
+                    subscription: Addr::unchecked("sub_2"),
+                    capital: 4_000,
+                    available_epoch_seconds: 0,
+                }],
+            )
+            .unwrap();
+        deps.querier
+            .base
+            .update_balance(MOCK_CONTRACT_ADDR, coins(15_000, "stable_coin"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLiabilities {}).unwrap();
+        let liabilities: Liabilities = from_binary(&res).unwrap();
+
+        // verify calls are not counted and coverage is balance over liabilities
+        assert_eq!(10_000, liabilities.redemptions.u128());
+        assert_eq!(10_000, liabilities.distributions.u128());
+        assert_eq!(20_000, liabilities.total.u128());
+        assert_eq!(15_000, liabilities.capital_balance.u128());
+        assert_eq!(Some(7_500), liabilities.coverage_bps);
+    }
+
+    #[test]
+    fn get_liabilities_none_owed() {
+        let deps = default_deps(None);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLiabilities {}).unwrap();
+        let liabilities: Liabilities = from_binary(&res).unwrap();
+
+        // verify no coverage ratio without liabilities
+        assert_eq!(0, liabilities.total.u128());
+        assert_eq!(None, liabilities.coverage_bps);
+    }
+
+    #[test]
+    fn get_liabilities_coverage_overflow() {
+        let mut deps = default_deps(None);
+        deps.querier
+            .base
+            .update_balance(MOCK_CONTRACT_ADDR, coins(u128::MAX, "stable_coin"));
+
+        // verify overflow is an error rather than a panic
+        assert_eq!(
+            "Generic error: liability coverage overflow",
+            query(deps.as_ref(), mock_env(), QueryMsg::GetLiabilities {})
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn get_raise_totals() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+        set_eligible(&mut deps.storage, vec!["sub_4"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            let exchange =
+                |investment: Option<i64>, commitment: Option<i64>, capital: i64| AssetExchange {
+                    investment,
+                    commitment_in_shares: commitment,
+                    capital: Some(capital),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                };
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![
+                        exchange(Some(-100), None, 1_000),
+                        exchange(Some(-50), None, 500),
+                    ],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_2").as_bytes(),
+                    &vec![exchange(Some(200), Some(-200), -2_000)],
+                )
+                .unwrap();
+            storage
+                .save(
+                    Addr::unchecked("sub_3").as_bytes(),
+                    &vec![exchange(None, Some(300), 0)],
+                )
+                .unwrap();
+        }
+        deps.querier.base.update_balance(
+            "sub_1",
+            vec![coin(400, "commitment_coin"), coin(600, "investment_coin")],
+        );
+        deps.querier.base.update_balance(
+            "sub_2",
+            vec![coin(500, "commitment_coin"), coin(100, "investment_coin")],
+        );
+        deps.querier
+            .base
+            .update_balance("sub_4", coins(1_000, "investment_coin"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRaiseTotals {}).unwrap();
+        let totals: RaiseTotals = from_binary(&res).unwrap();
+
+        // verify pending calls and unclaimed commitments adjust committed shares
+        assert_eq!(1_000, totals.total_committed_shares.u128());
+        // verify only accepted subscriptions are counted
+        assert_eq!(700, totals.total_invested_shares.u128());
+        assert_eq!(1_500, totals.total_outstanding_redemption_capital.u128());
+        assert_eq!(3, totals.accepted_subscription_count);
+    }
+
+    #[test]
+    fn get_raise_totals_too_many_subscriptions() {
+        let mut deps = default_deps(None);
+        let subscriptions: Vec<String> = (0..=MAX_RAISE_TOTALS_SUBSCRIPTIONS)
+            .map(|i| format!("sub_{}", i))
+            .collect();
+        set_accepted(
+            &mut deps.storage,
+            subscriptions.iter().map(|sub| sub.as_str()).collect(),
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRaiseTotals {});
+
+        // verify callers are pointed at pagination
+        assert!(res.unwrap_err().to_string().contains("pagination"));
+    }
+
+    #[test]
+    fn get_subscriptions_paginated() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_3", "sub_1", "sub_2"]);
+        set_eligible(&mut deps.storage, vec!["sub_4"]);
+
+        let page = |status: SubscriptionStatus,
+                    start_after: Option<&str>,
+                    limit: Option<u32>|
+         -> Vec<Addr> {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetSubscriptions {
+                    status,
+                    start_after: start_after.map(Addr::unchecked),
+                    limit,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+
+        // verify pages are sorted and bounded
+        assert_eq!(
+            vec![Addr::unchecked("sub_1"), Addr::unchecked("sub_2")],
+            page(SubscriptionStatus::Accepted, None, Some(2))
+        );
+        assert_eq!(
+            vec![Addr::unchecked("sub_3")],
+            page(SubscriptionStatus::Accepted, Some("sub_2"), Some(2))
+        );
+
+        // verify only the requested status is returned
+        assert_eq!(
+            vec![Addr::unchecked("sub_4")],
+            page(SubscriptionStatus::Eligible, None, None)
+        );
+        assert!(page(SubscriptionStatus::Pending, None, None).is_empty());
+    }
+
+    #[test]
+    fn export_cap_table() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            let exchange = |investment: i64, capital: i64| AssetExchange {
+                investment: Some(investment),
+                commitment_in_shares: None,
+                capital: Some(capital),
+                date: None,
+                issued_at: None,
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
+            };
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![exchange(1_000, -100_000), exchange(-250, 25_000)],
+                )
+                .unwrap();
+        }
+        // sub_1 still has its call queued while sub_2 has fully settled
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_2"), coins(500, "investment_coin"));
+        for (subscription, called) in [("sub_1", 100_000), ("sub_2", 50_000)] {
+            called_capital_storage(&mut deps.storage)
+                .save(Addr::unchecked(subscription).as_bytes(), &called)
+                .unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ExportCapTable {
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let entries: Vec<CapTableEntry> = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                CapTableEntry {
+                    subscription: Addr::unchecked("sub_1"),
+                    net_shares: 750,
+                    net_capital: -75_000,
+                },
+                CapTableEntry {
+                    subscription: Addr::unchecked("sub_2"),
+                    net_shares: 500,
+                    net_capital: -50_000,
+                },
+            ],
+            entries
+        );
+
+        // verify the next page starts after the last returned subscription
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ExportCapTable {
+                start_after: Some(Addr::unchecked("sub_2")),
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let entries: Vec<CapTableEntry> = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![CapTableEntry {
+                subscription: Addr::unchecked("sub_3"),
+                net_shares: 0,
+                net_capital: 0,
+            }],
+            entries
+        );
+    }
+
+    #[test]
+    fn why_not_eligible_missing_accreditation() {
+        let mut deps = mock_sub_state();
+        deps.querier.base.with_attributes("lp", &[("506b", "", "")]);
+        let mut state = State::test_default();
+        state.acceptable_accreditations = vec![String::from("506c")].into_iter().collect();
+        config(&mut deps.storage).save(&state).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::WhyNotEligible {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        )
+        .unwrap();
+        let reasons: Vec<IneligibilityReason> = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![IneligibilityReason::MissingAccreditation {
+                acceptable: vec![String::from("506c")].into_iter().collect(),
+            }],
+            reasons
+        );
+    }
+
+    #[test]
+    fn get_incomplete_subscriptions() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        wire_reference_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &String::from("WIRE-0001"),
+            )
+            .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetIncompleteSubscriptions {
+                require: vec![FieldKind::WireReference],
+            },
+        )
+        .unwrap();
+        let incomplete: Vec<Addr> = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("sub_2")], incomplete);
+    }
+
+    #[test]
+    fn get_flat_state() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        set_accepted(&mut deps.storage, vec!["sub_2", "sub_3"]);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_2").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(-1_000),
+                    commitment_in_shares: None,
+                    capital: Some(100_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFlatState {}).unwrap();
+
+        let flat: FlatState = from_binary(&res).unwrap();
+        assert_eq!(
+            FlatState {
+                gp: Addr::unchecked("gp"),
+                recovery_admin: Addr::unchecked("marketpalace"),
+                commitment_denom: String::from("commitment_coin"),
+                investment_denom: String::from("investment_coin"),
+                capital_denom: String::from("stable_coin"),
+                capital_per_share: 100,
+                config_locked: false,
+                redemptions_paused: false,
+                allow_lp_claims: false,
+                pending_count: 0,
+                eligible_count: 1,
+                accepted_count: 2,
+                frozen_count: 0,
+                outstanding_redemption_asset: Uint128::new(1_000),
+                outstanding_redemption_capital: Uint128::new(100_000),
+            },
+            flat
+        );
+    }
+
+    #[test]
+    fn validate_redemption_pair() {
+        let deps = default_deps(None);
+        let validate = |asset: u64, capital: u64| -> bool {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ValidateRedemptionPair { asset, capital },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+
+        assert!(validate(100, 10_000));
+        assert!(!validate(99, 10_000));
+        assert!(!validate(100, 10_050));
+    }
+
+    #[test]
+    fn convert_shares_and_capital() {
+        let deps = default_deps(None);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertSharesToCapital { shares: 100 },
+        )
+        .unwrap();
+        assert_eq!(10_000u64, from_binary::<u64>(&res).unwrap());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertCapitalToShares { capital: 10_050 },
+        )
+        .unwrap();
+        assert_eq!(100u64, from_binary::<u64>(&res).unwrap());
+
+        // verify overflow is an error rather than a panic
+        assert!(query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ConvertSharesToCapital { shares: u64::MAX },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn get_last_activity() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLastActivity {}).unwrap();
+        let last_activity: Option<LastActivity> = from_binary(&res).unwrap();
+
+        // verify last activity reflects the accept's block
+        assert_eq!(
+            LastActivity {
+                height: mock_env().block.height,
+                time: mock_env().block.time,
+            },
+            last_activity.unwrap()
+        );
+    }
+
+    #[test]
+    fn get_last_activity_ignores_statements() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::CloseRaise {},
+        )
+        .unwrap();
+
+        // emit a statement in a later block
+        let mut env = mock_env();
+        env.block.height += 1;
+        env.block.time = env.block.time.plus_seconds(5);
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("gp", &[]),
+            HandleMsg::EmitStatement {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLastActivity {}).unwrap();
+        let last_activity: Option<LastActivity> = from_binary(&res).unwrap();
+
+        // verify last activity still reflects the close
+        assert_eq!(
+            LastActivity {
+                height: mock_env().block.height,
+                time: mock_env().block.time,
+            },
+            last_activity.unwrap()
+        );
+    }
+
+    #[test]
+    fn get_unique_lp_count() {
+        // both subscriptions resolve to the same lp
+        let mut deps = mock_sub_state();
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetUniqueLpCount {}).unwrap();
+        let count: u64 = from_binary(&res).unwrap();
+        assert_eq!(1, count);
+    }
+
+    #[test]
+    fn get_asset_exchanges_since() {
+        let mut deps = mock_dependencies(&[]);
+        let exchange = |date: Option<ExchangeDate>| AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![
+                    exchange(Some(ExchangeDate::Due(1672531200))), // Jan 01 2023 UTC
+                    exchange(Some(ExchangeDate::Available(1675209600))), // Feb 01 2023 UTC
+                    exchange(Some(ExchangeDate::Due(1677628800))), // Mar 01 2023 UTC
+                    exchange(None),
+                ],
+            )
+            .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAssetExchangesSince {
+                subscription: Addr::unchecked("sub_1"),
+                since_epoch_seconds: 1675209600,
+            },
+        )
+        .unwrap();
+        let exchanges: Vec<AssetExchange> = from_binary(&res).unwrap();
+
+        // verify only exchanges dated on or after the cutoff are returned
+        assert_eq!(
+            vec![
+                exchange(Some(ExchangeDate::Available(1675209600))),
+                exchange(Some(ExchangeDate::Due(1677628800))),
+            ],
+            exchanges
+        );
+    }
+
+    #[test]
+    fn get_asset_exchanges_in_range() {
+        let mut deps = mock_dependencies(&[]);
+        let exchange = |date: Option<ExchangeDate>| AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(1_000),
+            date,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![
+                    exchange(Some(ExchangeDate::Due(1672531200))), // Jan 01 2023 UTC
+                    exchange(Some(ExchangeDate::Available(1675209600))), // Feb 01 2023 UTC
+                    exchange(Some(ExchangeDate::Due(1677628800))), // Mar 01 2023 UTC
+                    exchange(Some(ExchangeDate::Available(1680307200))), // Apr 01 2023 UTC
+                    exchange(None),
+                ],
+            )
+            .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAssetExchangesInRange {
+                subscription: Addr::unchecked("sub_1"),
+                start: 1675209600,
+                end: 1677628800,
+            },
+        )
+        .unwrap();
+        let exchanges: Vec<AssetExchange> = from_binary(&res).unwrap();
+
+        // verify only exchanges dated within the window are returned
+        assert_eq!(
+            vec![
+                exchange(Some(ExchangeDate::Available(1675209600))),
+                exchange(Some(ExchangeDate::Due(1677628800))),
+            ],
+            exchanges
+        );
+    }
+
+    #[test]
+    fn get_asset_exchanges_in_inverted_range() {
+        let deps = mock_dependencies(&[]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAssetExchangesInRange {
+                subscription: Addr::unchecked("sub_1"),
+                start: 1677628800,
+                end: 1675209600,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn get_capital_call_notices() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::NoticeCapitalCall {
+                subscriptions: vec![Addr::unchecked("sub_1")].into_iter().collect(),
+                amount: 10_000,
+                effective_epoch_seconds: 1675209600,
+                memo: None,
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCapitalCallNotices {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        )
+        .unwrap();
+        let notices: Vec<CapitalCallNotice> = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![CapitalCallNotice {
+                amount: 10_000,
+                effective_epoch_seconds: 1675209600,
+                memo: None,
+            }],
+            notices
+        );
+    }
+
+    #[test]
+    fn get_transfer_restriction() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::SetTransferRestriction {
+                subscription: Addr::unchecked("sub_1"),
+                restricted_until_epoch_seconds: 1675209600,
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTransferRestriction {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        )
+        .unwrap();
+        let restricted_until: Option<u64> = from_binary(&res).unwrap();
+        assert_eq!(Some(1675209600), restricted_until);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTransferRestriction {
+                subscription: Addr::unchecked("sub_2"),
+            },
+        )
+        .unwrap();
+        let restricted_until: Option<u64> = from_binary(&res).unwrap();
+        assert_eq!(None, restricted_until);
+    }
+
+    #[test]
+    fn get_redemptions_with_countdown() {
+        let mut deps = mock_dependencies(&[]);
+        let exchange = |investment: i64, date: Option<ExchangeDate>| AssetExchange {
+            investment: Some(investment),
+            commitment_in_shares: None,
+            capital: Some(-investment * 100),
+            date,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![
+                    exchange(-1_000, Some(ExchangeDate::Available(1672531200))), // Jan 01 2023 UTC
+                    exchange(-1_000, Some(ExchangeDate::Available(1677628800))), // Mar 01 2023 UTC
+                    exchange(-1_000, Some(ExchangeDate::Due(1677628800))),
+                    exchange(-1_000, None),
+                    exchange(1_000, Some(ExchangeDate::Available(1677628800))),
+                ],
+            )
+            .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRedemptionsWithCountdown {
+                subscription: Addr::unchecked("sub_1"),
+                now_epoch_seconds: 1675209600, // Feb 01 2023 UTC
+            },
+        )
+        .unwrap();
+        let countdowns: Vec<RedemptionCountdown> = from_binary(&res).unwrap();
+
+        // verify only redemptions are listed, counting down to future availability
+        assert_eq!(
+            vec![0, 2419200, 0, 0],
+            countdowns
+                .iter()
+                .map(|countdown| countdown.seconds_until_available)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn get_redemptions() {
+        let mut deps = mock_dependencies(&[]);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        let redemption = |date: Option<ExchangeDate>| AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(100_000),
+            date,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        let now = mock_env().block.time.seconds();
+        {
+            let mut storage = asset_exchange_storage(&mut deps.storage);
+            storage
+                .save(
+                    Addr::unchecked("sub_1").as_bytes(),
+                    &vec![
+                        redemption(Some(ExchangeDate::Available(now + 100))),
+                        redemption(Some(ExchangeDate::Available(now - 100))),
+                        AssetExchange {
+                            investment: Some(1_000),
+                            ..redemption(None)
+                        },
+                    ],
+                )
+                .unwrap();
+            storage
+                .save(Addr::unchecked("sub_2").as_bytes(), &vec![redemption(None)])
+                .unwrap();
+        }
+
+        let get_redemptions = |subscription: Option<&str>, only_available: bool| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetRedemptions {
+                    subscription: subscription.map(Addr::unchecked),
+                    only_available,
+                },
+            )
+            .unwrap();
+            from_binary::<Vec<SubscriptionRedemption>>(&res)
+                .unwrap()
+                .into_iter()
+                .map(|redemption| (redemption.subscription, redemption.exchange.date))
+                .collect::<Vec<_>>()
+        };
+
+        // verify all redemptions are sorted by availability
+        assert_eq!(
+            vec![
+                (Addr::unchecked("sub_2"), None),
+                (
+                    Addr::unchecked("sub_1"),
+                    Some(ExchangeDate::Available(now - 100))
+                ),
+                (
+                    Addr::unchecked("sub_1"),
+                    Some(ExchangeDate::Available(now + 100))
+                ),
+            ],
+            get_redemptions(None, false)
+        );
+
+        // verify future redemptions are dropped for a single sub
+        assert_eq!(
+            vec![(
+                Addr::unchecked("sub_1"),
+                Some(ExchangeDate::Available(now - 100))
+            )],
+            get_redemptions(Some("sub_1"), true)
+        );
+    }
+
+    #[test]
+    fn plan_claims() {
+        let mut deps = mock_dependencies(&[]);
+        let redemption = |date: Option<ExchangeDate>| AssetExchange {
+            investment: Some(-1_000),
+            commitment_in_shares: None,
+            capital: Some(100_000),
+            date,
+            issued_at: None,
+            installments: vec![],
+            gp_note: None,
+            memo: None,
+            expires_at: None,
+        };
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![
+                    redemption(Some(ExchangeDate::Available(1672531200))), // Jan 01 2023 UTC
+                    redemption(Some(ExchangeDate::Available(1677628800))), // Mar 01 2023 UTC
+                    redemption(None),
+                ],
+            )
+            .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PlanClaims {
+                subscription: Addr::unchecked("sub_1"),
+                available_funds: 1_500,
+                now_epoch_seconds: 1675209600, // Feb 01 2023 UTC
+            },
+        )
+        .unwrap();
+        let plan: ClaimPlan = from_binary(&res).unwrap();
+
+        // verify only the first matured redemption fits, leaving half of the next
+        assert_eq!(
+            ClaimPlan {
+                exchanges: vec![redemption(Some(ExchangeDate::Available(1672531200)))],
+                leftover: 500,
+            },
+            plan
+        );
+    }
+
+    #[test]
+    fn can_perform() {
+        let deps = default_deps(None);
+        let can_perform = |action: ActionKind, sender: &str| -> bool {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::CanPerform {
+                    action,
+                    sender: Addr::unchecked(sender),
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+
+        // verify the gp can accept but an lp can't
+        assert!(can_perform(ActionKind::Accept, "gp"));
+        assert!(!can_perform(ActionKind::Accept, "lp"));
+
+        // verify an lp can propose
+        assert!(can_perform(ActionKind::Propose, "lp"));
+    }
+
+    #[test]
+    fn get_subscription_terms() {
+        let deps = wasm_smart_mock_dependencies(&[], |_, _| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&SubTerms {
+                    lp: Addr::unchecked("lp"),
+                    raise: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                    capital_denom: String::from("stable_coin"),
+                    capital_per_share: 100,
+                    min_commitment: Some(10_000),
+                    max_commitment: None,
+                })
+                .unwrap(),
+            ))
+        });
+
+        let terms: SubTerms = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetSubscriptionTerms {
+                    subscription: Addr::unchecked("sub_1"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(Addr::unchecked("lp"), terms.lp);
+        assert_eq!(Some(10_000), terms.min_commitment);
+        assert_eq!(None, terms.max_commitment);
+    }
+
+    #[test]
+    fn get_subscription_terms_without_response() {
+        let deps = wasm_smart_mock_dependencies(&[], |contract, _| {
+            SystemResult::Err(SystemError::NoSuchContract { addr: contract })
+        });
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSubscriptionTerms {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        );
+        assert_eq!(
+            "Generic error: subscription sub_1 did not return its terms",
+            res.unwrap_err().to_string()
+        );
+    }
 }
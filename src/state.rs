@@ -2,21 +2,42 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-use cosmwasm_std::{Addr, Storage};
+use cosmwasm_std::{Addr, Coin, StdError, StdResult, Storage, Timestamp};
 use cosmwasm_storage::{
     bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
     Singleton,
 };
 
-use crate::msg::AssetExchange;
+use crate::error::ContractError;
+use crate::msg::{ActionKind, AssetExchange, CapitalCallNotice, Distribution, InstantiateMsg};
 
 pub static CONFIG_KEY: &[u8] = b"config";
+pub static LAST_ACTIVITY_KEY: &[u8] = b"last_activity";
+pub static PENDING_COMMITMENT_KEY: &[u8] = b"pending_commitment";
+pub static PENDING_GP_KEY: &[u8] = b"pending_gp";
+pub static PENDING_LP_KEY: &[u8] = b"pending_lp";
+pub static PROCESSED_IDEMPOTENCY_KEYS_KEY: &[u8] = b"processed_idempotency_keys";
+pub static GENESIS_CONFIG_KEY: &[u8] = b"genesis_config";
 
 pub static ASSET_EXCHANGE_NAMESPACE: &[u8] = b"asset_exchange";
+pub static LOCKUP_NAMESPACE: &[u8] = b"lockup";
+pub static WIRE_REFERENCE_NAMESPACE: &[u8] = b"wire_reference";
+pub static REDEEMED_CAPITAL_NAMESPACE: &[u8] = b"redeemed_capital";
+pub static PROPOSED_COMMITMENT_NAMESPACE: &[u8] = b"proposed_commitment";
+pub static UNACCEPTED_COMMITMENT_NAMESPACE: &[u8] = b"unaccepted_commitment";
+pub static REDEMPTION_CAP_NAMESPACE: &[u8] = b"redemption_cap";
+pub static COMMITMENT_CAP_NAMESPACE: &[u8] = b"commitment_cap";
+pub static CALLED_CAPITAL_NAMESPACE: &[u8] = b"called_capital";
+pub static CAPITAL_CALL_NOTICE_NAMESPACE: &[u8] = b"capital_call_notice";
+pub static ROUNDING_RESIDUAL_NAMESPACE: &[u8] = b"rounding_residual";
+pub static TRANSFER_RESTRICTION_NAMESPACE: &[u8] = b"transfer_restriction";
+pub static OUTSTANDING_DISTRIBUTIONS_NAMESPACE: &[u8] = b"outstanding_distributions";
+pub static LP_TO_SUBSCRIPTION_NAMESPACE: &[u8] = b"lp_to_subscription";
 
 pub static PENDING_SUBSCRIPTIONS_KEY: &[u8] = b"pending_subscriptions";
 pub static ELIGIBLE_SUBSCRIPTIONS_KEY: &[u8] = b"eligible_subscriptions";
 pub static ACCEPTED_SUBSCRIPTIONS_KEY: &[u8] = b"accepted_subscriptions";
+pub static FROZEN_SUBSCRIPTIONS_KEY: &[u8] = b"frozen_subscriptions";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
@@ -28,15 +49,115 @@ pub struct State {
     pub investment_denom: String,
     pub capital_denom: String,
     pub capital_per_share: u64,
+    #[serde(default)]
+    pub subscription_instantiate_funds: Vec<Coin>,
+    #[serde(default)]
+    pub config_locked: bool,
+    #[serde(default)]
+    pub redemptions_paused: bool,
+    #[serde(default)]
+    pub allow_lp_claims: bool,
+    #[serde(default)]
+    pub commitment_match_tolerance_bps: Option<u64>,
+    #[serde(default)]
+    pub min_accept_increment: Option<u64>,
+    #[serde(default)]
+    pub accreditation_source: Option<Addr>,
+    #[serde(default)]
+    pub redemption_rounding: Option<RoundingPolicy>,
+    #[serde(default)]
+    pub mint_commitment_on_accept: bool,
+    #[serde(default)]
+    pub max_accepted_subscriptions: Option<u64>,
+    #[serde(default)]
+    pub min_commitment: Option<u64>,
+    #[serde(default)]
+    pub max_commitment: Option<u64>,
+    #[serde(default)]
+    pub proposal_fee: Option<Coin>,
+    #[serde(default)]
+    pub fee_recipient: Option<Addr>,
+    #[serde(default)]
+    pub paused_actions: HashSet<ActionKind>,
+    #[serde(default)]
+    pub min_matching_accreditations: u32,
+    #[serde(default)]
+    pub restrict_redemption_destination: bool,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub accreditation_groups: Vec<HashSet<String>>,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingPolicy {
+    Floor,
+    Nearest,
+}
+
+impl RoundingPolicy {
+    // rounds a value scaled by 10000 (bps) to whole units
+    pub fn round_bps(&self, scaled: i128) -> i128 {
+        match self {
+            RoundingPolicy::Floor => scaled.div_euclid(10_000),
+            RoundingPolicy::Nearest => (scaled + 5_000).div_euclid(10_000),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LastActivity {
+    pub height: u64,
+    pub time: Timestamp,
 }
 
 impl State {
+    // nothing divides evenly into a zero capital per share
     pub fn not_evenly_divisble(&self, amount: u64) -> bool {
-        amount % self.capital_per_share > 0
+        !matches!(amount.checked_rem(self.capital_per_share), Some(0))
+    }
+
+    pub fn capital_to_shares(&self, amount: u64) -> StdResult<u64> {
+        amount
+            .checked_div(self.capital_per_share)
+            .ok_or_else(|| StdError::generic_err("capital_per_share must be non-zero"))
+    }
+
+    pub fn shares_to_capital(&self, shares: u64) -> StdResult<u64> {
+        shares
+            .checked_mul(self.capital_per_share)
+            .ok_or_else(|| StdError::generic_err("shares to capital overflow"))
+    }
+
+    pub fn ensure_distinct_denoms(&self) -> Result<(), ContractError> {
+        if self.commitment_denom.is_empty()
+            || self.investment_denom.is_empty()
+            || self.capital_denom.is_empty()
+        {
+            return Err(ContractError::from("denoms must not be empty"));
+        }
+
+        if self.commitment_denom == self.investment_denom
+            || self.commitment_denom == self.capital_denom
+            || self.investment_denom == self.capital_denom
+        {
+            return Err(ContractError::from(
+                "commitment, investment and capital denoms must be distinct",
+            ));
+        }
+
+        Ok(())
     }
 
-    pub fn capital_to_shares(&self, amount: u64) -> u64 {
-        amount / self.capital_per_share
+    pub fn ensure_config_unlocked(&self) -> Result<(), ContractError> {
+        if self.config_locked {
+            return Err(ContractError::from("config is locked"));
+        }
+
+        Ok(())
     }
 }
 
@@ -48,6 +169,38 @@ pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<State> {
     singleton_read(storage, CONFIG_KEY)
 }
 
+pub fn last_activity(storage: &mut dyn Storage) -> Singleton<LastActivity> {
+    singleton(storage, LAST_ACTIVITY_KEY)
+}
+
+pub fn last_activity_read(storage: &dyn Storage) -> ReadonlySingleton<LastActivity> {
+    singleton_read(storage, LAST_ACTIVITY_KEY)
+}
+
+pub fn pending_commitment(storage: &mut dyn Storage) -> Singleton<u64> {
+    singleton(storage, PENDING_COMMITMENT_KEY)
+}
+
+pub fn pending_commitment_read(storage: &dyn Storage) -> ReadonlySingleton<u64> {
+    singleton_read(storage, PENDING_COMMITMENT_KEY)
+}
+
+pub fn pending_gp(storage: &mut dyn Storage) -> Singleton<Addr> {
+    singleton(storage, PENDING_GP_KEY)
+}
+
+pub fn pending_gp_read(storage: &dyn Storage) -> ReadonlySingleton<Addr> {
+    singleton_read(storage, PENDING_GP_KEY)
+}
+
+pub fn genesis_config(storage: &mut dyn Storage) -> Singleton<InstantiateMsg> {
+    singleton(storage, GENESIS_CONFIG_KEY)
+}
+
+pub fn genesis_config_read(storage: &dyn Storage) -> ReadonlySingleton<InstantiateMsg> {
+    singleton_read(storage, GENESIS_CONFIG_KEY)
+}
+
 pub fn asset_exchange_storage(storage: &mut dyn Storage) -> Bucket<Vec<AssetExchange>> {
     bucket(storage, ASSET_EXCHANGE_NAMESPACE)
 }
@@ -56,6 +209,128 @@ pub fn asset_exchange_storage_read(storage: &dyn Storage) -> ReadonlyBucket<Vec<
     bucket_read(storage, ASSET_EXCHANGE_NAMESPACE)
 }
 
+pub fn outstanding_distributions(storage: &mut dyn Storage) -> Bucket<Vec<Distribution>> {
+    bucket(storage, OUTSTANDING_DISTRIBUTIONS_NAMESPACE)
+}
+
+pub fn outstanding_distributions_read(storage: &dyn Storage) -> ReadonlyBucket<Vec<Distribution>> {
+    bucket_read(storage, OUTSTANDING_DISTRIBUTIONS_NAMESPACE)
+}
+
+pub fn lockup_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, LOCKUP_NAMESPACE)
+}
+
+pub fn lockup_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, LOCKUP_NAMESPACE)
+}
+
+pub fn wire_reference_storage(storage: &mut dyn Storage) -> Bucket<String> {
+    bucket(storage, WIRE_REFERENCE_NAMESPACE)
+}
+
+pub fn wire_reference_storage_read(storage: &dyn Storage) -> ReadonlyBucket<String> {
+    bucket_read(storage, WIRE_REFERENCE_NAMESPACE)
+}
+
+pub fn redeemed_capital_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, REDEEMED_CAPITAL_NAMESPACE)
+}
+
+pub fn redeemed_capital_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, REDEEMED_CAPITAL_NAMESPACE)
+}
+
+pub fn pending_lp(storage: &mut dyn Storage) -> Singleton<Addr> {
+    singleton(storage, PENDING_LP_KEY)
+}
+
+pub fn pending_lp_read(storage: &dyn Storage) -> ReadonlySingleton<Addr> {
+    singleton_read(storage, PENDING_LP_KEY)
+}
+
+pub fn processed_idempotency_keys(storage: &mut dyn Storage) -> Singleton<Vec<String>> {
+    singleton(storage, PROCESSED_IDEMPOTENCY_KEYS_KEY)
+}
+
+pub fn processed_idempotency_keys_read(storage: &dyn Storage) -> ReadonlySingleton<Vec<String>> {
+    singleton_read(storage, PROCESSED_IDEMPOTENCY_KEYS_KEY)
+}
+
+pub fn lp_to_subscription(storage: &mut dyn Storage) -> Bucket<HashSet<Addr>> {
+    bucket(storage, LP_TO_SUBSCRIPTION_NAMESPACE)
+}
+
+pub fn lp_to_subscription_read(storage: &dyn Storage) -> ReadonlyBucket<HashSet<Addr>> {
+    bucket_read(storage, LP_TO_SUBSCRIPTION_NAMESPACE)
+}
+
+pub fn proposed_commitment_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, PROPOSED_COMMITMENT_NAMESPACE)
+}
+
+pub fn proposed_commitment_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, PROPOSED_COMMITMENT_NAMESPACE)
+}
+
+pub fn unaccepted_commitment_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, UNACCEPTED_COMMITMENT_NAMESPACE)
+}
+
+pub fn unaccepted_commitment_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, UNACCEPTED_COMMITMENT_NAMESPACE)
+}
+
+pub fn redemption_cap_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, REDEMPTION_CAP_NAMESPACE)
+}
+
+pub fn redemption_cap_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, REDEMPTION_CAP_NAMESPACE)
+}
+
+pub fn commitment_cap_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, COMMITMENT_CAP_NAMESPACE)
+}
+
+pub fn commitment_cap_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, COMMITMENT_CAP_NAMESPACE)
+}
+
+pub fn called_capital_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, CALLED_CAPITAL_NAMESPACE)
+}
+
+pub fn called_capital_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, CALLED_CAPITAL_NAMESPACE)
+}
+
+pub fn capital_call_notice_storage(storage: &mut dyn Storage) -> Bucket<Vec<CapitalCallNotice>> {
+    bucket(storage, CAPITAL_CALL_NOTICE_NAMESPACE)
+}
+
+pub fn rounding_residual_storage(storage: &mut dyn Storage) -> Bucket<i64> {
+    bucket(storage, ROUNDING_RESIDUAL_NAMESPACE)
+}
+
+pub fn rounding_residual_storage_read(storage: &dyn Storage) -> ReadonlyBucket<i64> {
+    bucket_read(storage, ROUNDING_RESIDUAL_NAMESPACE)
+}
+
+pub fn transfer_restriction_storage(storage: &mut dyn Storage) -> Bucket<u64> {
+    bucket(storage, TRANSFER_RESTRICTION_NAMESPACE)
+}
+
+pub fn transfer_restriction_storage_read(storage: &dyn Storage) -> ReadonlyBucket<u64> {
+    bucket_read(storage, TRANSFER_RESTRICTION_NAMESPACE)
+}
+
+pub fn capital_call_notice_storage_read(
+    storage: &dyn Storage,
+) -> ReadonlyBucket<Vec<CapitalCallNotice>> {
+    bucket_read(storage, CAPITAL_CALL_NOTICE_NAMESPACE)
+}
+
 pub fn pending_subscriptions(storage: &mut dyn Storage) -> Singleton<HashSet<Addr>> {
     singleton(storage, PENDING_SUBSCRIPTIONS_KEY)
 }
@@ -80,6 +355,14 @@ pub fn accepted_subscriptions_read(storage: &dyn Storage) -> ReadonlySingleton<H
     singleton_read(storage, ACCEPTED_SUBSCRIPTIONS_KEY)
 }
 
+pub fn frozen_subscriptions(storage: &mut dyn Storage) -> Singleton<HashSet<Addr>> {
+    singleton(storage, FROZEN_SUBSCRIPTIONS_KEY)
+}
+
+pub fn frozen_subscriptions_read(storage: &dyn Storage) -> ReadonlySingleton<HashSet<Addr>> {
+    singleton_read(storage, FROZEN_SUBSCRIPTIONS_KEY)
+}
+
 #[cfg(test)]
 pub mod tests {
     use cosmwasm_storage::{bucket_read, ReadonlyBucket};
@@ -97,6 +380,26 @@ pub mod tests {
                 investment_denom: String::from("investment_coin"),
                 capital_denom: String::from("stable_coin"),
                 capital_per_share: 100,
+                subscription_instantiate_funds: vec![],
+                config_locked: false,
+                redemptions_paused: false,
+                allow_lp_claims: false,
+                commitment_match_tolerance_bps: None,
+                min_accept_increment: None,
+                accreditation_source: None,
+                redemption_rounding: None,
+                mint_commitment_on_accept: false,
+                max_accepted_subscriptions: None,
+                min_commitment: None,
+                max_commitment: None,
+                proposal_fee: None,
+                fee_recipient: None,
+                paused_actions: HashSet::new(),
+                min_matching_accreditations: 0,
+                restrict_redemption_destination: false,
+                closed: false,
+                accreditation_groups: vec![],
+                paused: false,
             }
         }
     }
@@ -132,6 +435,45 @@ pub mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn ensure_distinct_denoms() {
+        assert!(State::test_default().ensure_distinct_denoms().is_ok());
+
+        let with_denoms = |commitment: &str, investment: &str, capital: &str| State {
+            commitment_denom: String::from(commitment),
+            investment_denom: String::from(investment),
+            capital_denom: String::from(capital),
+            ..State::test_default()
+        };
+        assert!(with_denoms("coin", "coin", "stable_coin")
+            .ensure_distinct_denoms()
+            .is_err());
+        assert!(with_denoms("coin", "investment_coin", "coin")
+            .ensure_distinct_denoms()
+            .is_err());
+        assert!(with_denoms("commitment_coin", "coin", "coin")
+            .ensure_distinct_denoms()
+            .is_err());
+        assert!(with_denoms("commitment_coin", "", "stable_coin")
+            .ensure_distinct_denoms()
+            .is_err());
+    }
+
+    #[test]
+    fn shares_to_capital() {
+        let state = State::test_default();
+
+        assert_eq!(0, state.shares_to_capital(0).unwrap());
+        assert_eq!(1_000, state.shares_to_capital(10).unwrap());
+        assert_eq!(
+            10,
+            state
+                .capital_to_shares(state.shares_to_capital(10).unwrap())
+                .unwrap()
+        );
+        assert!(state.shares_to_capital(u64::MAX).is_err());
+    }
+
     #[test]
     fn not_evenly_divisble() {
         let state = State::test_default();
@@ -140,5 +482,13 @@ pub mod tests {
         assert!(state.not_evenly_divisble(101));
         assert_eq!(false, state.not_evenly_divisble(1_000));
         assert!(state.not_evenly_divisble(1_001));
+
+        // verify a zero capital per share rejects rather than panics
+        let state = State {
+            capital_per_share: 0,
+            ..State::test_default()
+        };
+        assert!(state.not_evenly_divisble(100));
+        assert!(state.capital_to_shares(100).is_err());
     }
 }
@@ -1,8 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::Addr;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct SubInstantiateMsg {
     pub admin: Addr,
@@ -18,6 +19,7 @@ pub struct SubInstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum SubQueryMsg {
     GetState {},
+    GetTerms {},
 }
 
 #[derive(Deserialize, Serialize)]
@@ -30,3 +32,15 @@ pub struct SubState {
     pub capital_denom: String,
     pub capital_per_share: u64,
 }
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SubTerms {
+    pub lp: Addr,
+    pub raise: Addr,
+    pub capital_denom: String,
+    pub capital_per_share: u64,
+    #[serde(default)]
+    pub min_commitment: Option<u64>,
+    #[serde(default)]
+    pub max_commitment: Option<u64>,
+}
@@ -1,18 +1,38 @@
+use crate::accounting::remaining_commitment;
+use crate::accreditation_msg::AccreditationQueryMsg;
 use crate::contract::ContractResponse;
-use crate::error::contract_error;
-use crate::msg::{AcceptSubscription, AssetExchange};
-use crate::state::{accepted_subscriptions, config_read, pending_subscriptions};
-use crate::state::{asset_exchange_storage, eligible_subscriptions};
+use crate::error::{contract_error, ContractError};
+use crate::exchange_asset::{validate_exchange_date, validate_exchange_expiry};
+use crate::msg::{
+    AcceptSubscription, AssetExchange, ExchangeDate, Redemption, SubscriptionInstantiatePreview,
+};
+use crate::state::commitment_cap_storage;
+use crate::state::unaccepted_commitment_storage;
+use crate::state::State;
+use crate::state::{accepted_subscriptions, config, config_read, pending_subscriptions};
+use crate::state::{
+    accepted_subscriptions_read, eligible_subscriptions_read, pending_subscriptions_read,
+};
+use crate::state::{asset_exchange_storage, eligible_subscriptions, wire_reference_storage};
+use crate::state::{
+    frozen_subscriptions_read, pending_commitment, proposed_commitment_storage,
+    proposed_commitment_storage_read,
+};
+use crate::state::{lp_to_subscription, lp_to_subscription_read, pending_lp};
 use crate::sub_msg::{SubInstantiateMsg, SubQueryMsg, SubState};
 use cosmwasm_std::MessageInfo;
 use cosmwasm_std::Response;
-use cosmwasm_std::{to_binary, Addr, Env, SubMsg, WasmMsg};
-use cosmwasm_std::{Deps, DepsMut};
+use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, Env, SubMsg, Uint128, WasmMsg};
+use cosmwasm_std::{Deps, DepsMut, StdResult};
 use provwasm_std::ProvenanceQuerier;
 use provwasm_std::ProvenanceQuery;
+use provwasm_std::{mint_marker_supply, withdraw_coins};
 use std::collections::HashSet;
 use std::convert::TryInto;
 
+const MAX_WIRE_REFERENCE_LENGTH: usize = 64;
+const MAX_REEVALUATE_BATCH_SIZE: usize = 25;
+
 pub fn try_propose_subscription(
     deps: DepsMut<ProvenanceQuery>,
     env: Env,
@@ -21,42 +41,196 @@ pub fn try_propose_subscription(
 ) -> ContractResponse {
     let state = config_read(deps.storage).load()?;
 
-    let eligible = if state.acceptable_accreditations.is_empty() {
-        true
-    } else {
-        let attributes = attributes(deps.as_ref(), &info.sender);
+    if state.closed {
+        return contract_error("raise is closed to new subscriptions");
+    }
 
-        attributes
-            .intersection(&state.acceptable_accreditations)
-            .count()
-            > 0
-    };
+    if let Some(max) = state.max_accepted_subscriptions {
+        let count = accepted_subscriptions_read(deps.storage)
+            .may_load()?
+            .unwrap_or_default()
+            .len()
+            + pending_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+                .len()
+            + eligible_subscriptions_read(deps.storage)
+                .may_load()?
+                .unwrap_or_default()
+                .len();
+        if count as u64 >= max {
+            return Err(ContractError::limit_exceeded("raise is at capacity"));
+        }
+    }
+
+    // proposals without a commitment defer bounds to acceptance
+    if let Some(commitment) = initial_commitment {
+        if let Some(min) = state.min_commitment {
+            if commitment < min {
+                return contract_error(&format!("commitment must be at least {}", min));
+            }
+        }
+
+        if let Some(max) = state.max_commitment {
+            if commitment > max {
+                return Err(ContractError::limit_exceeded(&format!(
+                    "commitment must be at most {}",
+                    max
+                )));
+            }
+        }
+    }
+
+    // seed funds come from the proposer so repeated proposals cannot drain the raise, and
+    // anything beyond them and the fee would be stranded in the raise
+    let mut required: Vec<Coin> = vec![];
+    for coin in state
+        .subscription_instantiate_funds
+        .iter()
+        .chain(state.proposal_fee.iter())
+    {
+        match required.iter_mut().find(|total| total.denom == coin.denom) {
+            Some(total) => total.amount += coin.amount,
+            None => required.push(coin.clone()),
+        }
+    }
+    if required
+        .iter()
+        .any(|coin| attached(&info.funds, &coin.denom) != coin.amount)
+        || info
+            .funds
+            .iter()
+            .any(|coin| !required.iter().any(|total| total.denom == coin.denom))
+    {
+        return Err(ContractError::insufficient_funds(&format!(
+            "funds must be exactly [{}]",
+            required
+                .iter()
+                .map(|coin| coin.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )));
+    }
+
+    let fee_send = state.proposal_fee.as_ref().map(|fee| BankMsg::Send {
+        to_address: state
+            .fee_recipient
+            .clone()
+            .unwrap_or_else(|| state.gp.clone())
+            .into_string(),
+        amount: vec![fee.clone()],
+    });
+
+    let preview = preview_subscription_instantiate(
+        deps.as_ref(),
+        &state,
+        info.sender.clone(),
+        initial_commitment,
+    )?;
+
+    // hold the proposed commitment and lp until the reply resolves the subscription address
+    match initial_commitment {
+        Some(commitment) => pending_commitment(deps.storage).save(&commitment)?,
+        None => pending_commitment(deps.storage).remove(),
+    }
+    pending_lp(deps.storage).save(&info.sender)?;
 
     let create_sub = SubMsg::reply_always(
         WasmMsg::Instantiate {
             admin: Some(env.contract.address.into_string()),
             code_id: state.subscription_code_id,
-            msg: to_binary(&SubInstantiateMsg {
-                admin: state.recovery_admin,
-                lp: info.sender,
-                commitment_denom: state.commitment_denom,
-                investment_denom: state.investment_denom,
-                capital_denom: state.capital_denom,
-                capital_per_share: state.capital_per_share,
-                initial_commitment,
-            })?,
-            funds: vec![],
+            msg: to_binary(&preview.msg)?,
+            funds: state.subscription_instantiate_funds,
             label: String::from("establish subscription"),
         },
-        if eligible { 1 } else { 0 },
+        preview.reply_id,
     );
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_submessage(create_sub)
-        .add_attribute("eligible", format!("{}", eligible)))
+        .add_attribute("action", "propose_subscription")
+        .add_attribute("eligible", format!("{}", preview.eligible));
+    if let Some(commitment) = initial_commitment {
+        response = response.add_attribute("initial_commitment", commitment.to_string());
+    }
+    if let Some(fee_send) = fee_send {
+        response = response.add_message(fee_send);
+    }
+
+    Ok(response)
+}
+
+fn attached(funds: &[Coin], denom: &str) -> Uint128 {
+    funds
+        .iter()
+        .filter(|coin| coin.denom == denom)
+        .map(|coin| coin.amount)
+        .sum()
+}
+
+// builds the subscription instantiate msg and reply id a proposal from lp would use
+pub fn preview_subscription_instantiate(
+    deps: Deps<ProvenanceQuery>,
+    state: &State,
+    lp: Addr,
+    initial_commitment: Option<u64>,
+) -> StdResult<SubscriptionInstantiatePreview> {
+    let acceptable_accreditations = resolve_accreditations(deps, state)?;
+    let eligible =
+        meets_accreditation_requirement(state, &acceptable_accreditations, &attributes(deps, &lp));
+
+    Ok(SubscriptionInstantiatePreview {
+        msg: SubInstantiateMsg {
+            admin: state.recovery_admin.clone(),
+            lp,
+            commitment_denom: state.commitment_denom.clone(),
+            investment_denom: state.investment_denom.clone(),
+            capital_denom: state.capital_denom.clone(),
+            capital_per_share: state.capital_per_share,
+            initial_commitment,
+        },
+        eligible,
+        reply_id: if eligible { 1 } else { 0 },
+    })
+}
+
+// a shared accreditation source takes precedence over the statically configured set
+pub fn resolve_accreditations(
+    deps: Deps<ProvenanceQuery>,
+    state: &State,
+) -> StdResult<HashSet<String>> {
+    match &state.accreditation_source {
+        Some(source) => deps
+            .querier
+            .query_wasm_smart(source.clone(), &AccreditationQueryMsg::GetAccreditations {}),
+        None => Ok(state.acceptable_accreditations.clone()),
+    }
+}
+
+pub fn requires_accreditation(state: &State, acceptable_accreditations: &HashSet<String>) -> bool {
+    !acceptable_accreditations.is_empty() || !state.accreditation_groups.is_empty()
+}
+
+// groups are an any-of of all-of sets and take precedence over the flat set,
+// which otherwise acts as a single any-of group where an unset minimum needs one match
+pub fn meets_accreditation_requirement(
+    state: &State,
+    acceptable_accreditations: &HashSet<String>,
+    attributes: &HashSet<String>,
+) -> bool {
+    if !state.accreditation_groups.is_empty() {
+        return state
+            .accreditation_groups
+            .iter()
+            .any(|group| group.is_subset(attributes));
+    }
+
+    acceptable_accreditations.is_empty()
+        || attributes.intersection(acceptable_accreditations).count()
+            >= state.min_matching_accreditations.max(1) as usize
 }
 
-fn attributes(deps: Deps<ProvenanceQuery>, lp: &Addr) -> HashSet<String> {
+pub fn attributes(deps: Deps<ProvenanceQuery>, lp: &Addr) -> HashSet<String> {
     ProvenanceQuerier::new(&deps.querier)
         .get_attributes(lp.clone(), None as Option<String>)
         .unwrap()
@@ -66,6 +240,259 @@ fn attributes(deps: Deps<ProvenanceQuery>, lp: &Addr) -> HashSet<String> {
         .collect()
 }
 
+pub fn try_update_accreditations(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    accreditations: HashSet<String>,
+    start_after: Option<Addr>,
+) -> ContractResponse {
+    let mut state = config_read(deps.storage).load()?;
+    let mut pending = pending_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut eligible = eligible_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can update accreditations",
+        ));
+    }
+
+    state.ensure_config_unlocked()?;
+
+    // the flat set is ignored while a source or groups are configured
+    if state.accreditation_source.is_some() || !state.accreditation_groups.is_empty() {
+        return contract_error(
+            "accreditations are resolved from the accreditation source or groups",
+        );
+    }
+
+    state.acceptable_accreditations = accreditations;
+    config(deps.storage).save(&state)?;
+
+    // sort so that each call evaluates a deterministic batch resumable from start_after
+    let mut candidates: Vec<Addr> = pending
+        .union(&eligible)
+        .filter(|subscription| match &start_after {
+            Some(start_after) => *subscription > start_after,
+            None => true,
+        })
+        .cloned()
+        .collect();
+    candidates.sort();
+    let remaining = candidates.len().saturating_sub(MAX_REEVALUATE_BATCH_SIZE);
+    candidates.truncate(MAX_REEVALUATE_BATCH_SIZE);
+
+    let acceptable_accreditations = resolve_accreditations(deps.as_ref(), &state)?;
+    let mut reclassified = 0;
+    for subscription in candidates.iter().cloned() {
+        let sub_state: SubState = deps
+            .querier
+            .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetState {})?;
+        if sub_state.raise != env.contract.address {
+            continue;
+        }
+
+        let now_eligible = meets_accreditation_requirement(
+            &state,
+            &acceptable_accreditations,
+            &attributes(deps.as_ref(), &sub_state.lp),
+        );
+        if now_eligible && pending.remove(&subscription) {
+            eligible.insert(subscription);
+            reclassified += 1;
+        } else if !now_eligible && eligible.remove(&subscription) {
+            pending.insert(subscription);
+            reclassified += 1;
+        }
+    }
+
+    pending_subscriptions(deps.storage).save(&pending)?;
+    eligible_subscriptions(deps.storage).save(&eligible)?;
+
+    Ok(Response::new()
+        .add_attribute("reclassified", reclassified.to_string())
+        .add_attribute("evaluated", candidates.len().to_string())
+        .add_attribute("remaining", remaining.to_string()))
+}
+
+pub fn try_withdraw_proposal(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+) -> ContractResponse {
+    let mut pending = pending_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut eligible = eligible_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    let owned = match lp_to_subscription_read(deps.storage).may_load(info.sender.as_bytes())? {
+        Some(subscriptions) => {
+            let mut owned: Vec<Addr> = subscriptions
+                .into_iter()
+                .filter(|sub| pending.contains(sub) || eligible.contains(sub))
+                .collect();
+            owned.sort();
+            owned.into_iter().next()
+        }
+        // subscriptions proposed before the lp mapping existed need a smart query each
+        None => {
+            let mut candidates: Vec<Addr> = pending.union(&eligible).cloned().collect();
+            candidates.sort();
+            let mut owned = None;
+            for subscription in candidates {
+                let sub_state: SubState = deps
+                    .querier
+                    .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetState {})?;
+                if sub_state.lp == info.sender {
+                    owned = Some(subscription);
+                    break;
+                }
+            }
+            owned
+        }
+    };
+
+    let subscription = match owned {
+        Some(subscription) => subscription,
+        None => {
+            return Err(ContractError::subscription_not_found(
+                "no pending subscription owned by sender",
+            ))
+        }
+    };
+
+    if accepted.contains(&subscription) {
+        return contract_error("subscription already accepted");
+    }
+
+    pending.remove(&subscription);
+    eligible.remove(&subscription);
+    proposed_commitment_storage(deps.storage).remove(subscription.as_bytes());
+
+    if let Some(mut owned) =
+        lp_to_subscription_read(deps.storage).may_load(info.sender.as_bytes())?
+    {
+        owned.remove(&subscription);
+        if owned.is_empty() {
+            lp_to_subscription(deps.storage).remove(info.sender.as_bytes());
+        } else {
+            lp_to_subscription(deps.storage).save(info.sender.as_bytes(), &owned)?;
+        }
+    }
+
+    pending_subscriptions(deps.storage).save(&pending)?;
+    eligible_subscriptions(deps.storage).save(&eligible)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_proposal")
+        .add_attribute("subscription", subscription))
+}
+
+// an address belongs to at most one set, keeping its furthest along membership
+pub fn try_repair_membership(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let mut pending = pending_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut eligible = eligible_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let accepted = accepted_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if info.sender != state.recovery_admin {
+        return Err(ContractError::unauthorized(
+            "only admin can repair membership",
+        ));
+    }
+
+    let mut response = Response::new();
+    let overlapping = |set: &HashSet<Addr>, keep: &HashSet<Addr>| {
+        let mut removed: Vec<Addr> = set.intersection(keep).cloned().collect();
+        removed.sort();
+        removed
+    };
+    for subscription in overlapping(&pending, &accepted.union(&eligible).cloned().collect()) {
+        pending.remove(&subscription);
+        response = response.add_attribute("removed_from_pending", subscription);
+    }
+    for subscription in overlapping(&eligible, &accepted) {
+        eligible.remove(&subscription);
+        response = response.add_attribute("removed_from_eligible", subscription);
+    }
+
+    pending_subscriptions(deps.storage).save(&pending)?;
+    eligible_subscriptions(deps.storage).save(&eligible)?;
+
+    Ok(response)
+}
+
+pub fn try_reevaluate_all_pending(
+    deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let mut pending = pending_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut eligible = eligible_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can reevaluate pending subscriptions",
+        ));
+    }
+
+    // sort so that each call evaluates a deterministic batch
+    let mut batch: Vec<Addr> = pending.iter().cloned().collect();
+    batch.sort();
+    batch.truncate(MAX_REEVALUATE_BATCH_SIZE);
+
+    let acceptable_accreditations = resolve_accreditations(deps.as_ref(), &state)?;
+    let mut promoted = 0;
+    for subscription in batch.iter() {
+        let sub_state: SubState = deps
+            .querier
+            .query_wasm_smart(subscription.clone(), &SubQueryMsg::GetState {})?;
+        if sub_state.raise != env.contract.address {
+            continue;
+        }
+
+        let newly_eligible = meets_accreditation_requirement(
+            &state,
+            &acceptable_accreditations,
+            &attributes(deps.as_ref(), &sub_state.lp),
+        );
+        if newly_eligible {
+            pending.remove(subscription);
+            eligible.insert(subscription.clone());
+            promoted += 1;
+        }
+    }
+
+    pending_subscriptions(deps.storage).save(&pending)?;
+    eligible_subscriptions(deps.storage).save(&eligible)?;
+
+    Ok(Response::new()
+        .add_attribute("evaluated", batch.len().to_string())
+        .add_attribute("promoted", promoted.to_string()))
+}
+
 pub fn try_close_subscriptions(
     deps: DepsMut<ProvenanceQuery>,
     info: MessageInfo,
@@ -83,37 +510,161 @@ pub fn try_close_subscriptions(
         .unwrap_or_default();
 
     if info.sender != state.gp {
-        return contract_error("only gp can close subscriptions");
+        return Err(ContractError::unauthorized(
+            "only gp can close subscriptions",
+        ));
     }
 
+    let frozen = frozen_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    let mut response = Response::new();
     for subscription in subscriptions {
+        if frozen.contains(&subscription) {
+            return Err(ContractError::frozen("subscription is frozen"));
+        }
+
         if !pending.remove(&subscription) && !eligible.remove(&subscription) {
             if accepted.contains(&subscription) {
-                let remaining_commitment = deps
-                    .querier
-                    .query_balance(subscription.as_str(), state.commitment_denom.clone())
-                    .map(|coin| coin.amount.u128())?;
-                if remaining_commitment == 0 {
+                if remaining_commitment(deps.as_ref(), &subscription)? == 0 {
                     accepted.remove(&subscription);
                     asset_exchange_storage(deps.storage).remove(subscription.as_bytes());
                 } else {
                     return contract_error("sub still has remaining commitment");
                 }
             } else {
-                return contract_error("no subscription pending or accepted to close");
+                return Err(ContractError::subscription_not_found(
+                    "no subscription pending or accepted to close",
+                ));
             }
         }
+
+        response = response
+            .add_attribute("action", "close_subscription")
+            .add_attribute("subscription", subscription);
     }
 
     pending_subscriptions(deps.storage).save(&pending)?;
     eligible_subscriptions(deps.storage).save(&eligible)?;
     accepted_subscriptions(deps.storage).save(&accepted)?;
 
-    Ok(Response::new())
+    Ok(response)
+}
+
+// sends eligible subscriptions back to pending for manual review
+pub fn try_requeue_subscriptions(
+    deps: DepsMut<ProvenanceQuery>,
+    info: MessageInfo,
+    subscriptions: HashSet<Addr>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+    let mut pending = pending_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+    let mut eligible = eligible_subscriptions(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    if info.sender != state.gp {
+        return Err(ContractError::unauthorized(
+            "only gp can requeue subscriptions",
+        ));
+    }
+
+    let mut subscriptions: Vec<Addr> = subscriptions.into_iter().collect();
+    subscriptions.sort();
+
+    let mut response = Response::new();
+    for subscription in subscriptions {
+        if !eligible.remove(&subscription) {
+            return Err(ContractError::subscription_not_found(&format!(
+                "subscription not eligible: {}",
+                subscription
+            )));
+        }
+        pending.insert(subscription.clone());
+
+        response = response
+            .add_attribute("action", "requeue_subscription")
+            .add_attribute("subscription", subscription);
+    }
+
+    pending_subscriptions(deps.storage).save(&pending)?;
+    eligible_subscriptions(deps.storage).save(&eligible)?;
+
+    Ok(response)
+}
+
+pub fn try_accept_with_redemption_schedule(
+    mut deps: DepsMut<ProvenanceQuery>,
+    env: Env,
+    info: MessageInfo,
+    subscription: Addr,
+    commitment_in_capital: u64,
+    redemptions: Vec<Redemption>,
+) -> ContractResponse {
+    let state = config_read(deps.storage).load()?;
+
+    let mut scheduled: u64 = 0;
+    for redemption in redemptions.iter() {
+        if redemption.asset == 0
+            || state.not_evenly_divisble(redemption.capital)
+            || redemption.asset != state.capital_to_shares(redemption.capital)?
+        {
+            return contract_error("redemption asset does not match capital");
+        }
+        validate_exchange_date(&env, &redemption.date)?;
+        validate_exchange_expiry(&env, &redemption.date, redemption.expires_epoch_seconds)?;
+        scheduled = scheduled
+            .checked_add(redemption.asset)
+            .ok_or("redemption schedule overflow")?;
+    }
+
+    if scheduled > state.capital_to_shares(commitment_in_capital)? {
+        return Err(ContractError::limit_exceeded(
+            "redemptions exceed accepted shares",
+        ));
+    }
+
+    let response = try_accept_subscriptions(
+        deps.branch(),
+        env.clone(),
+        info,
+        vec![AcceptSubscription {
+            subscription: subscription.clone(),
+            commitment_in_capital,
+            wire_reference: None,
+            max_commitment_override: None,
+            commitment_in_shares: None,
+        }],
+    )?;
+
+    let mut storage = asset_exchange_storage(deps.storage);
+    let mut exchanges = storage
+        .may_load(subscription.as_bytes())?
+        .unwrap_or_default();
+    for redemption in redemptions.iter() {
+        exchanges.push(AssetExchange {
+            investment: Some(-(redemption.asset.try_into()?)),
+            commitment_in_shares: None,
+            capital: Some(redemption.capital.try_into()?),
+            date: redemption.date.clone(),
+            issued_at: Some(env.block.time.seconds()),
+            installments: vec![],
+            gp_note: None,
+            memo: redemption.memo.clone(),
+            expires_at: redemption.expires_epoch_seconds,
+        });
+    }
+    storage.save(subscription.as_bytes(), &exchanges)?;
+
+    Ok(response.add_attribute("scheduled_redemptions", redemptions.len().to_string()))
 }
 
 pub fn try_accept_subscriptions(
     deps: DepsMut<ProvenanceQuery>,
+    env: Env,
     info: MessageInfo,
     accepts: Vec<AcceptSubscription>,
 ) -> ContractResponse {
@@ -129,80 +680,241 @@ pub fn try_accept_subscriptions(
         .unwrap_or_default();
 
     if info.sender != state.gp {
-        return contract_error("only gp can accept subscriptions");
+        return Err(ContractError::unauthorized(
+            "only gp can accept subscriptions",
+        ));
+    }
+
+    // a repeated subscription would otherwise fail later as no longer pending or eligible
+    let mut seen = HashSet::new();
+    if !accepts
+        .iter()
+        .all(|accept| seen.insert(&accept.subscription))
+    {
+        return contract_error("duplicate subscription in batch");
     }
 
+    let acceptable_accreditations = resolve_accreditations(deps.as_ref(), &state)?;
+
+    let frozen = frozen_subscriptions_read(deps.storage)
+        .may_load()?
+        .unwrap_or_default();
+
+    let mut response = Response::new();
     for accept in accepts.iter() {
-        if state.not_evenly_divisble(accept.commitment_in_capital) {
-            return contract_error("accept amount must be evenly divisble by capital per share");
+        if frozen.contains(&accept.subscription) {
+            return Err(ContractError::frozen("subscription is frozen"));
         }
 
-        if eligible.contains(&accept.subscription) {
-            eligible.remove(&accept.subscription);
-        } else if pending.contains(&accept.subscription) {
-            if !state.acceptable_accreditations.is_empty() {
-                let sub_state: SubState = deps
-                    .querier
-                    .query_wasm_smart(accept.subscription.clone(), &SubQueryMsg::GetState {})?;
+        // shares are taken verbatim, so only a capital amount needs to divide evenly
+        let (commitment_in_capital, commitment_in_shares) = match accept.commitment_in_shares {
+            Some(_) if accept.commitment_in_capital > 0 => {
+                return contract_error("accept must be in either capital or shares, not both");
+            }
+            Some(shares) => match state.shares_to_capital(shares) {
+                Ok(capital) => (capital, Ok(shares)),
+                Err(_) => return contract_error("capital conversion overflow"),
+            },
+            None => {
+                if state.not_evenly_divisble(accept.commitment_in_capital) {
+                    return Err(ContractError::not_divisible(
+                        "accept amount must be evenly divisble by capital per share",
+                    ));
+                }
+                (
+                    accept.commitment_in_capital,
+                    state.capital_to_shares(accept.commitment_in_capital),
+                )
+            }
+        };
+
+        if commitment_in_capital == 0 {
+            return contract_error("accept amount must be greater than zero");
+        }
+
+        // outstanding commitment is recorded as signed shares, so it must also fit an i64
+        let commitment_in_shares = match commitment_in_shares {
+            Ok(shares) if shares <= i64::MAX as u64 => shares,
+            _ => return contract_error("capital conversion overflow"),
+        };
+
+        // an override lets a gp size the cap per lp instead of the raise wide max
+        if let Some(max) = accept.max_commitment_override.or(state.max_commitment) {
+            if commitment_in_capital > max {
+                return Err(ContractError::limit_exceeded(&format!(
+                    "commitment must be at most {}",
+                    max
+                )));
+            }
+        }
+
+        if let Some(increment) = state.min_accept_increment {
+            if increment > 0 && commitment_in_capital % increment > 0 {
+                return Err(ContractError::not_divisible(&format!(
+                    "accept amount must be a multiple of {}",
+                    increment
+                )));
+            }
+        }
 
-                let attributes: HashSet<String> = attributes(deps.as_ref(), &sub_state.lp);
+        let proposed = proposed_commitment_storage_read(deps.storage)
+            .may_load(accept.subscription.as_bytes())?;
 
-                if attributes
-                    .intersection(&state.acceptable_accreditations)
-                    .count()
-                    == 0
+        if let Some(tolerance_bps) = state.commitment_match_tolerance_bps {
+            if let Some(proposed) = proposed {
+                let difference = commitment_in_capital.abs_diff(proposed);
+                if u128::from(difference) * 10_000
+                    > u128::from(proposed) * u128::from(tolerance_bps)
                 {
                     return contract_error(
-                        "subscription owner must have one of acceptable accreditations",
+                        "accepted commitment does not match proposed commitment",
                     );
                 }
             }
+        }
+
+        if let Some(wire_reference) = &accept.wire_reference {
+            if wire_reference.is_empty() || wire_reference.len() > MAX_WIRE_REFERENCE_LENGTH {
+                return contract_error(&format!(
+                    "wire reference must be between 1 and {} characters",
+                    MAX_WIRE_REFERENCE_LENGTH
+                ));
+            }
+        }
 
-            pending.remove(&accept.subscription);
+        let was_pending = if eligible.remove(&accept.subscription) {
+            false
+        } else if pending.remove(&accept.subscription) {
+            true
         } else {
-            return contract_error("subscription must either be pending or eligible");
+            return Err(ContractError::subscription_not_found(
+                "subscription must either be pending or eligible",
+            ));
+        };
+
+        // accepting more than was proposed re-checks the owner even if already eligible
+        let exceeds_proposed = match proposed {
+            Some(proposed) => commitment_in_capital > proposed,
+            None => false,
+        };
+
+        if requires_accreditation(&state, &acceptable_accreditations)
+            && (was_pending || exceeds_proposed)
+        {
+            let sub_state: SubState = deps
+                .querier
+                .query_wasm_smart(accept.subscription.clone(), &SubQueryMsg::GetState {})?;
+
+            if sub_state.raise != env.contract.address {
+                return Err(ContractError::subscription_not_found(
+                    "subscription belongs to a different raise",
+                ));
+            }
+
+            let attributes: HashSet<String> = attributes(deps.as_ref(), &sub_state.lp);
+
+            if !meets_accreditation_requirement(&state, &acceptable_accreditations, &attributes) {
+                return contract_error(
+                    "subscription owner must have the required acceptable accreditations",
+                );
+            }
+        }
+
+        if let Some(proposed) = proposed {
+            if commitment_in_capital < proposed {
+                unaccepted_commitment_storage(deps.storage).save(
+                    accept.subscription.as_bytes(),
+                    &(proposed - commitment_in_capital),
+                )?;
+            }
         }
 
         accepted.insert(accept.subscription.clone());
-        asset_exchange_storage(deps.storage).save(
-            accept.subscription.as_bytes(),
-            &vec![AssetExchange {
-                investment: None,
-                commitment_in_shares: Some(
-                    state
-                        .capital_to_shares(accept.commitment_in_capital)
-                        .try_into()?,
-                ),
-                capital: None,
-                date: None,
-            }],
-        )?;
+
+        response = response
+            .add_attribute("action", "accept_subscription")
+            .add_attribute("subscription", accept.subscription.clone())
+            .add_attribute("commitment_in_capital", commitment_in_capital.to_string())
+            .add_attribute("commitment_in_shares", commitment_in_shares.to_string());
+        if let Some(proposed) = proposed {
+            if commitment_in_capital < proposed {
+                response = response.add_attribute("partial_acceptance", "true");
+            }
+        }
+
+        // commitment delivered up front is read back from the sub's balance instead of an
+        // exchange. like accept exchanges and capital calls, the commitment denom counts shares
+        if state.mint_commitment_on_accept {
+            response = response
+                .add_message(mint_marker_supply(
+                    commitment_in_shares.into(),
+                    state.commitment_denom.clone(),
+                )?)
+                .add_message(withdraw_coins(
+                    state.commitment_denom.clone(),
+                    commitment_in_shares.into(),
+                    state.commitment_denom.clone(),
+                    accept.subscription.clone(),
+                )?);
+        } else {
+            asset_exchange_storage(deps.storage).save(
+                accept.subscription.as_bytes(),
+                &vec![AssetExchange {
+                    investment: None,
+                    commitment_in_shares: Some(commitment_in_shares.try_into()?),
+                    capital: None,
+                    date: Some(ExchangeDate::Available(env.block.time.seconds())),
+                    issued_at: Some(env.block.time.seconds()),
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )?;
+        }
+
+        if let Some(wire_reference) = &accept.wire_reference {
+            wire_reference_storage(deps.storage)
+                .save(accept.subscription.as_bytes(), wire_reference)?;
+        }
+
+        match accept.max_commitment_override {
+            Some(cap) => {
+                commitment_cap_storage(deps.storage).save(accept.subscription.as_bytes(), &cap)?
+            }
+            None => commitment_cap_storage(deps.storage).remove(accept.subscription.as_bytes()),
+        }
     }
 
     pending_subscriptions(deps.storage).save(&pending)?;
     eligible_subscriptions(deps.storage).save(&eligible)?;
     accepted_subscriptions(deps.storage).save(&accepted)?;
 
-    Ok(Response::default())
+    Ok(response)
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
     use crate::contract::execute;
     use crate::contract::tests::default_deps;
     use crate::mock::{
-        instantiate_args, msg_at_index, wasm_smart_mock_dependencies, MockContractQuerier,
+        instantiate_args, mint_args, msg_at_index, send_args, wasm_smart_mock_dependencies,
+        withdraw_args, MockContractQuerier,
     };
+    use crate::msg::CapitalCall;
     use crate::msg::HandleMsg;
     use crate::msg::QueryMsg;
     use crate::msg::RaiseState;
     use crate::query::query;
+    use crate::state::commitment_cap_storage_read;
     use crate::state::config;
     use crate::state::pending_subscriptions_read;
+    use crate::state::proposed_commitment_storage;
     use crate::state::tests::to_addresses;
     use crate::state::tests::{asset_exchange_storage_read, set_accepted};
     use crate::state::tests::{set_eligible, set_pending};
+    use crate::state::unaccepted_commitment_storage_read;
     use crate::state::State;
     use crate::state::{accepted_subscriptions_read, eligible_subscriptions_read};
     use cosmwasm_std::coins;
@@ -210,8 +922,11 @@ mod tests {
     use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::testing::mock_info;
     use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
     use cosmwasm_std::to_binary;
     use cosmwasm_std::Addr;
+    use cosmwasm_std::Attribute;
+    use cosmwasm_std::Coin;
     use cosmwasm_std::ContractResult;
     use cosmwasm_std::MemoryStorage;
     use cosmwasm_std::OwnedDeps;
@@ -224,7 +939,7 @@ mod tests {
                 to_binary(&SubState {
                     admin: Addr::unchecked("marketpalace"),
                     lp: Addr::unchecked("lp"),
-                    raise: Addr::unchecked("raise_1"),
+                    raise: Addr::unchecked(MOCK_CONTRACT_ADDR),
                     commitment_denom: String::from("raise_1.commitment"),
                     investment_denom: String::from("raise_1.investment"),
                     capital_denom: String::from("stable_coin"),
@@ -271,15 +986,162 @@ mod tests {
         assert_eq!(0, funds.len());
         assert_eq!("establish subscription", label);
         assert_eq!(
-            "false",
+            vec![
+                Attribute::new("action", "propose_subscription"),
+                Attribute::new("eligible", "false"),
+                Attribute::new("initial_commitment", "100"),
+            ],
             res.attributes
-                .iter()
-                .find(|attr| attr.key == "eligible")
-                .unwrap()
-                .value
         );
     }
 
+    fn propose_with_min_matching(lp_accreditations: &[&str]) -> String {
+        let mut deps = default_deps(Some(|state| {
+            state.acceptable_accreditations = vec!["506b", "506c", "qp"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+            state.min_matching_accreditations = 2;
+        }));
+        let attributes: Vec<(&str, &str, &str)> = lp_accreditations
+            .iter()
+            .map(|name| (*name, "", ""))
+            .collect();
+        deps.querier.with_attributes("lp", &attributes);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        )
+        .unwrap();
+
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == "eligible")
+            .unwrap()
+            .value
+            .clone()
+    }
+
+    #[test]
+    fn propose_subscription_min_matching_accreditations() {
+        // verify fewer than the minimum is not eligible
+        assert_eq!("false", propose_with_min_matching(&["506c"]));
+
+        // verify exactly the minimum is eligible
+        assert_eq!("true", propose_with_min_matching(&["506b", "506c"]));
+
+        // verify more than the minimum is eligible
+        assert_eq!("true", propose_with_min_matching(&["506b", "506c", "qp"]));
+    }
+
+    fn propose_with_accreditation_groups(lp_accreditations: &[&str]) -> String {
+        let mut deps = default_deps(Some(|state| {
+            state.accreditation_groups = vec![vec!["506c", "kyc"], vec!["qp"]]
+                .into_iter()
+                .map(|group| group.into_iter().map(String::from).collect())
+                .collect();
+        }));
+        let attributes: Vec<(&str, &str, &str)> = lp_accreditations
+            .iter()
+            .map(|name| (*name, "", ""))
+            .collect();
+        deps.querier.with_attributes("lp", &attributes);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        )
+        .unwrap();
+
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == "eligible")
+            .unwrap()
+            .value
+            .clone()
+    }
+
+    #[test]
+    fn propose_subscription_accreditation_group_requires_all() {
+        // verify part of a group is not eligible even though it is in the flat set
+        assert_eq!("false", propose_with_accreditation_groups(&["506c"]));
+
+        // verify the whole group is eligible
+        assert_eq!("true", propose_with_accreditation_groups(&["506c", "kyc"]));
+    }
+
+    #[test]
+    fn propose_subscription_accreditation_groups_any_of() {
+        // verify any single satisfied group is eligible
+        assert_eq!("true", propose_with_accreditation_groups(&["qp"]));
+
+        // verify no satisfied group is not eligible
+        assert_eq!("false", propose_with_accreditation_groups(&["kyc", "506b"]));
+    }
+
+    #[test]
+    fn accept_subscription_missing_accreditation_group() {
+        let mut deps = mock_sub_state();
+        deps.querier.base.with_attributes("lp", &[("506c", "", "")]);
+        let mut state = State::test_default();
+        state.accreditation_groups = vec![vec![String::from("506c"), String::from("kyc")]
+            .into_iter()
+            .collect()];
+        config(&mut deps.storage).save(&state).unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            accept_with_override(20_000, None),
+        );
+        assert_eq!(
+            "Generic error: subscription owner must have the required acceptable accreditations",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_below_min_matching_accreditations() {
+        let mut deps = mock_sub_state();
+        deps.querier.base.with_attributes("lp", &[("506c", "", "")]);
+        let mut state = State::test_default();
+        state.acceptable_accreditations = vec![String::from("506b"), String::from("506c")]
+            .into_iter()
+            .collect();
+        state.min_matching_accreditations = 2;
+        config(&mut deps.storage).save(&state).unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }]
+                .into_iter()
+                .collect(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
     #[test]
     fn propose_eligible_subscription() {
         let mut deps = default_deps(None);
@@ -326,13 +1188,243 @@ mod tests {
         );
     }
 
+    #[test]
+    fn propose_subscription_with_instantiate_funds() {
+        let mut deps = default_deps(Some(|state| {
+            state.subscription_instantiate_funds = coins(1_000, "nhash");
+        }));
+
+        // propose a sub as lp
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &coins(1_000, "nhash")),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: Some(100),
+            },
+        )
+        .unwrap();
+
+        // verify instantiate message carries the configured funds
+        let (_, _, _, funds, _) = instantiate_args::<SubInstantiateMsg>(msg_at_index(&res, 0));
+        assert_eq!(&coins(1_000, "nhash"), funds);
+    }
+
+    #[test]
+    fn preview_subscription_instantiate_matches_proposal() {
+        let mut deps = default_deps(None);
+        deps.querier.with_attributes("lp", &[("506c", "", "")]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: Some(100),
+            },
+        )
+        .unwrap();
+
+        let preview: SubscriptionInstantiatePreview = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PreviewSubscriptionInstantiate {
+                    lp: Addr::unchecked("lp"),
+                    initial_commitment: Some(100),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        // verify the preview matches the proposed instantiate msg and reply id
+        let (_, _, msg, _, _) = instantiate_args::<SubInstantiateMsg>(msg_at_index(&res, 0));
+        assert_eq!(preview.msg, msg);
+        assert_eq!(preview.reply_id, res.messages[0].id);
+        assert!(preview.eligible);
+    }
+
+    #[test]
+    fn propose_subscription_under_capacity() {
+        let mut deps = default_deps(Some(|state| {
+            state.max_accepted_subscriptions = Some(3);
+        }));
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        set_pending(&mut deps.storage, vec!["sub_2"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn propose_subscription_at_capacity() {
+        let mut deps = default_deps(Some(|state| {
+            state.max_accepted_subscriptions = Some(3);
+        }));
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        set_pending(&mut deps.storage, vec!["sub_2"]);
+        set_eligible(&mut deps.storage, vec!["sub_3"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        );
+
+        assert!(res.is_err());
+    }
+
+    fn propose_with_bounds(initial_commitment: Option<u64>) -> ContractResponse {
+        let mut deps = default_deps(Some(|state| {
+            state.min_commitment = Some(10_000);
+            state.max_commitment = Some(100_000);
+        }));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription { initial_commitment },
+        )
+    }
+
+    #[test]
+    fn propose_subscription_below_min_commitment() {
+        assert!(propose_with_bounds(Some(9_999)).is_err());
+    }
+
+    #[test]
+    fn propose_subscription_above_max_commitment() {
+        assert!(propose_with_bounds(Some(100_001)).is_err());
+    }
+
+    #[test]
+    fn propose_subscription_at_commitment_bounds() {
+        assert!(propose_with_bounds(Some(10_000)).is_ok());
+        assert!(propose_with_bounds(Some(100_000)).is_ok());
+    }
+
+    #[test]
+    fn propose_subscription_without_commitment_skips_bounds() {
+        assert!(propose_with_bounds(None).is_ok());
+    }
+
+    fn propose_with_fee(funds: &[Coin]) -> ContractResponse {
+        let mut deps = default_deps(Some(|state| {
+            state.proposal_fee = Some(Coin::new(1_000, "nhash"));
+            state.fee_recipient = Some(Addr::unchecked("fee_recipient"));
+        }));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", funds),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        )
+    }
+
+    #[test]
+    fn propose_subscription_with_proposal_fee() {
+        let res = propose_with_fee(&coins(1_000, "nhash")).unwrap();
+
+        // verify the fee is forwarded after the instantiate
+        assert_eq!(2, res.messages.len());
+        let (to_address, amount) = send_args(msg_at_index(&res, 1));
+        assert_eq!("fee_recipient", to_address);
+        assert_eq!(&coins(1_000, "nhash"), amount);
+    }
+
+    #[test]
+    fn propose_subscription_with_insufficient_proposal_fee() {
+        assert!(propose_with_fee(&coins(999, "nhash")).is_err());
+    }
+
+    #[test]
+    fn propose_subscription_with_wrong_proposal_fee_denom() {
+        assert!(propose_with_fee(&coins(1_000, "stable_coin")).is_err());
+    }
+
+    #[test]
+    fn propose_subscription_with_proposal_fee_overpayment() {
+        let res = propose_with_fee(&coins(1_500, "nhash"));
+        match res.unwrap_err() {
+            ContractError::InsufficientFunds { msg } => {
+                assert_eq!("funds must be exactly [1000nhash]", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        // verify other coins can't ride along with the fee either
+        assert!(
+            propose_with_fee(&[Coin::new(1_000, "nhash"), Coin::new(1, "stable_coin")]).is_err()
+        );
+    }
+
+    #[test]
+    fn propose_subscription_with_instantiate_funds_and_proposal_fee() {
+        let mut deps = default_deps(Some(|state| {
+            state.subscription_instantiate_funds = coins(1_000, "nhash");
+            state.proposal_fee = Some(Coin::new(500, "nhash"));
+        }));
+
+        // verify seed funds and the fee are paid together
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &coins(1_500, "nhash")),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        )
+        .unwrap();
+        let (_, _, _, funds, _) = instantiate_args::<SubInstantiateMsg>(msg_at_index(&res, 0));
+        assert_eq!(&coins(1_000, "nhash"), funds);
+        let (to_address, amount) = send_args(msg_at_index(&res, 1));
+        assert_eq!("gp", to_address);
+        assert_eq!(&coins(500, "nhash"), amount);
+    }
+
+    #[test]
+    fn propose_subscription_with_insufficient_instantiate_funds() {
+        let mut deps = default_deps(Some(|state| {
+            state.subscription_instantiate_funds = coins(1_000, "nhash");
+        }));
+        deps.querier
+            .base
+            .update_balance(MOCK_CONTRACT_ADDR, coins(5_000, "nhash"));
+
+        // verify the raise's own balance is not spent on a proposer without funds
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: Some(100),
+            },
+        );
+        assert!(matches!(res, Err(ContractError::InsufficientFunds { .. })));
+    }
+
     #[test]
     fn close_pending_subscriptions() {
         let mut deps = default_deps(None);
         set_pending(&mut deps.storage, vec!["sub_1"]);
 
         // close sub as gp
-        execute(
+        let res = execute(
             deps.as_mut(),
             mock_env(),
             mock_info("gp", &[]),
@@ -342,6 +1434,15 @@ mod tests {
         )
         .unwrap();
 
+        // verify close attributes
+        assert_eq!(
+            vec![
+                Attribute::new("action", "close_subscription"),
+                Attribute::new("subscription", "sub_1"),
+            ],
+            res.attributes
+        );
+
         // verify pending sub is removed
         assert_eq!(
             0,
@@ -379,53 +1480,128 @@ mod tests {
     }
 
     #[test]
-    fn close_subscriptions_accepted_no_commitment() {
+    fn requeue_subscriptions() {
         let mut deps = default_deps(None);
-        set_accepted(&mut deps.storage, vec!["sub_1"]);
-        asset_exchange_storage(&mut deps.storage)
-            .save(
-                Addr::unchecked("sub_1").as_bytes(),
-                &vec![AssetExchange {
-                    investment: Some(1_000),
-                    commitment_in_shares: Some(-1_000),
-                    capital: Some(-1_000),
-                    date: None,
-                }],
-            )
-            .unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1", "sub_2"]);
 
-        // close sub as gp
-        execute(
+        let res = execute(
             deps.as_mut(),
             mock_env(),
             mock_info("gp", &[]),
-            HandleMsg::CloseSubscriptions {
+            HandleMsg::RequeueSubscriptions {
                 subscriptions: to_addresses(vec!["sub_1"]),
             },
         )
         .unwrap();
 
-        // verify accepted sub is removed
+        // verify requeue attributes
         assert_eq!(
-            0,
-            accepted_subscriptions_read(&deps.storage)
-                .load()
-                .unwrap()
-                .len()
+            vec![
+                Attribute::new("action", "requeue_subscription"),
+                Attribute::new("subscription", "sub_1"),
+            ],
+            res.attributes
         );
 
-        // verify remove any outstanding asset exchanges
-        assert!(asset_exchange_storage_read(&deps.storage)
-            .may_load(Addr::unchecked("sub_1").as_bytes())
-            .unwrap()
-            .is_none());
+        // verify the sub moved from eligible to pending
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
+        let state: RaiseState = from_binary(&res).unwrap();
+        assert_eq!(to_addresses(vec!["sub_1"]), state.pending_subscriptions);
+        assert_eq!(to_addresses(vec!["sub_2"]), state.eligible_subscriptions);
     }
 
     #[test]
-    fn close_subscriptions_accepted_commitment() {
+    fn requeue_subscriptions_not_eligible() {
         let mut deps = default_deps(None);
-        config(&mut deps.storage)
-            .save(&&State::test_default())
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+        set_eligible(&mut deps.storage, vec!["sub_2"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::RequeueSubscriptions {
+                subscriptions: to_addresses(vec!["sub_1", "sub_2"]),
+            },
+        );
+        assert!(res.is_err());
+
+        // verify nothing moved
+        assert_eq!(
+            to_addresses(vec!["sub_2"]),
+            eligible_subscriptions_read(&deps.storage).load().unwrap()
+        );
+    }
+
+    #[test]
+    fn requeue_subscriptions_bad_actor() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::RequeueSubscriptions {
+                subscriptions: to_addresses(vec!["sub_1"]),
+            },
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized { .. })));
+    }
+
+    #[test]
+    fn close_subscriptions_accepted_no_commitment() {
+        let mut deps = default_deps(None);
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+        asset_exchange_storage(&mut deps.storage)
+            .save(
+                Addr::unchecked("sub_1").as_bytes(),
+                &vec![AssetExchange {
+                    investment: Some(1_000),
+                    commitment_in_shares: Some(-1_000),
+                    capital: Some(-1_000),
+                    date: None,
+                    issued_at: None,
+                    installments: vec![],
+                    gp_note: None,
+                    memo: None,
+                    expires_at: None,
+                }],
+            )
+            .unwrap();
+
+        // close sub as gp
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::CloseSubscriptions {
+                subscriptions: to_addresses(vec!["sub_1"]),
+            },
+        )
+        .unwrap();
+
+        // verify accepted sub is removed
+        assert_eq!(
+            0,
+            accepted_subscriptions_read(&deps.storage)
+                .load()
+                .unwrap()
+                .len()
+        );
+
+        // verify remove any outstanding asset exchanges
+        assert!(asset_exchange_storage_read(&deps.storage)
+            .may_load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn close_subscriptions_accepted_commitment() {
+        let mut deps = default_deps(None);
+        config(&mut deps.storage)
+            .save(&&State::test_default())
             .unwrap();
         set_accepted(&mut deps.storage, vec!["sub_1"]);
         deps.querier
@@ -482,6 +1658,131 @@ mod tests {
         assert!(res.is_err());
     }
 
+    fn accept_with_schedule(
+        redemptions: Vec<Redemption>,
+    ) -> (
+        OwnedDeps<MemoryStorage, MockApi, MockContractQuerier, ProvenanceQuery>,
+        ContractResponse,
+    ) {
+        let mut deps = mock_sub_state();
+        deps.querier.base.with_attributes("lp", &[("506c", "", "")]);
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptWithRedemptionSchedule {
+                subscription: Addr::unchecked("sub_1"),
+                commitment_in_capital: 20_000,
+                redemptions,
+            },
+        );
+
+        (deps, res)
+    }
+
+    #[test]
+    fn accept_with_redemption_schedule() {
+        let (deps, res) = accept_with_schedule(vec![
+            Redemption {
+                asset: 50,
+                capital: 5_000,
+                date: Some(ExchangeDate::Available(1672531200)),
+                memo: Some(String::from("q3 distribution")),
+                expires_epoch_seconds: None,
+            },
+            Redemption {
+                asset: 150,
+                capital: 15_000,
+                date: Some(ExchangeDate::Available(1704067200)),
+                memo: None,
+                expires_epoch_seconds: None,
+            },
+        ]);
+        res.unwrap();
+
+        // verify the sub is accepted
+        assert!(accepted_subscriptions_read(&deps.storage)
+            .load()
+            .unwrap()
+            .contains(&Addr::unchecked("sub_1")));
+
+        // verify the commitment exchange and both scheduled redemptions
+        let exchanges = asset_exchange_storage_read(&deps.storage)
+            .load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap();
+        assert_eq!(3, exchanges.len());
+        assert_eq!(Some(200), exchanges[0].commitment_in_shares);
+        assert_eq!(
+            AssetExchange {
+                investment: Some(-50),
+                commitment_in_shares: None,
+                capital: Some(5_000),
+                date: Some(ExchangeDate::Available(1672531200)),
+                issued_at: Some(mock_env().block.time.seconds()),
+                installments: vec![],
+                gp_note: None,
+                memo: Some(String::from("q3 distribution")),
+                expires_at: None,
+            },
+            exchanges[1]
+        );
+        assert_eq!(Some(-150), exchanges[2].investment);
+        assert_eq!(Some(15_000), exchanges[2].capital);
+    }
+
+    #[test]
+    fn accept_with_redemption_schedule_exceeding_shares() {
+        let (deps, res) = accept_with_schedule(vec![Redemption {
+            asset: 201,
+            capital: 20_100,
+            date: None,
+            memo: None,
+            expires_epoch_seconds: None,
+        }]);
+        assert!(res.is_err());
+
+        // verify nothing was accepted
+        assert!(accepted_subscriptions_read(&deps.storage)
+            .may_load()
+            .unwrap()
+            .unwrap_or_default()
+            .is_empty());
+    }
+
+    #[test]
+    fn accept_with_redemption_schedule_overflow() {
+        let capital = u64::MAX - u64::MAX % 100;
+        let redemption = Redemption {
+            asset: capital / 100,
+            capital,
+            date: None,
+            memo: None,
+            expires_epoch_seconds: None,
+        };
+        let (_, res) = accept_with_schedule(vec![redemption; 101]);
+        assert_eq!(
+            "Generic error: redemption schedule overflow",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn accept_with_redemption_schedule_mismatched_pair() {
+        let (_, res) = accept_with_schedule(vec![Redemption {
+            asset: 50,
+            capital: 4_000,
+            date: None,
+            memo: None,
+            expires_epoch_seconds: None,
+        }]);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn accept_pending_subscription() {
         let mut deps = mock_sub_state();
@@ -500,6 +1801,9 @@ mod tests {
                 subscriptions: vec![AcceptSubscription {
                     subscription: Addr::unchecked("sub_1"),
                     commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
                 }]
                 .into_iter()
                 .collect(),
@@ -513,13 +1817,18 @@ mod tests {
         assert_eq!(0, state.pending_subscriptions.len());
         assert_eq!(1, state.accepted_subscriptions.len());
 
-        // verify asset exchange exists
+        // verify asset exchange exists, effective as of acceptance
         assert_eq!(
             &AssetExchange {
                 investment: None,
                 commitment_in_shares: Some(200),
                 capital: None,
-                date: None,
+                date: Some(ExchangeDate::Available(mock_env().block.time.seconds())),
+                issued_at: Some(mock_env().block.time.seconds()),
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
             },
             asset_exchange_storage_read(&mut deps.storage)
                 .load(Addr::unchecked("sub_1").as_bytes())
@@ -530,6 +1839,53 @@ mod tests {
         )
     }
 
+    #[test]
+    fn propose_subscription_after_close() {
+        let mut deps = mock_sub_state();
+        deps.querier.base.with_attributes("lp", &[("506c", "", "")]);
+        config(&mut deps.storage)
+            .save(&State {
+                closed: true,
+                ..State::test_default()
+            })
+            .unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        );
+        assert_eq!(
+            "Generic error: raise is closed to new subscriptions",
+            res.unwrap_err().to_string()
+        );
+
+        // verify existing subscriptions can still be accepted
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            to_addresses(vec!["sub_1"]),
+            accepted_subscriptions_read(&deps.storage).load().unwrap()
+        );
+    }
+
     #[test]
     fn accept_eligible_subscription() {
         let mut deps = mock_sub_state();
@@ -548,6 +1904,9 @@ mod tests {
                 subscriptions: vec![AcceptSubscription {
                     subscription: Addr::unchecked("sub_1"),
                     commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
                 }]
                 .into_iter()
                 .collect(),
@@ -561,13 +1920,18 @@ mod tests {
         assert_eq!(0, state.eligible_subscriptions.len());
         assert_eq!(1, state.accepted_subscriptions.len());
 
-        // verify asset exchange exists
+        // verify asset exchange exists, effective as of acceptance
         assert_eq!(
             &AssetExchange {
                 investment: None,
                 commitment_in_shares: Some(200),
                 capital: None,
-                date: None,
+                date: Some(ExchangeDate::Available(mock_env().block.time.seconds())),
+                issued_at: Some(mock_env().block.time.seconds()),
+                installments: vec![],
+                gp_note: None,
+                memo: None,
+                expires_at: None,
             },
             asset_exchange_storage_read(&mut deps.storage)
                 .load(Addr::unchecked("sub_1").as_bytes())
@@ -592,6 +1956,9 @@ mod tests {
                 subscriptions: vec![AcceptSubscription {
                     subscription: Addr::unchecked("sub_1"),
                     commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
                 }]
                 .into_iter()
                 .collect(),
@@ -613,6 +1980,9 @@ mod tests {
                 subscriptions: vec![AcceptSubscription {
                     subscription: Addr::unchecked("sub_1"),
                     commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
                 }]
                 .into_iter()
                 .collect(),
@@ -622,51 +1992,1160 @@ mod tests {
     }
 
     #[test]
-    fn accept_subscription_missing_acceptable_accreditation() {
-        let mut deps = mock_sub_state();
-
-        let mut state = State::test_default();
-        state.acceptable_accreditations = vec![String::from("506c")].into_iter().collect();
-        config(&mut deps.storage).save(&state).unwrap();
-
-        set_pending(&mut deps.storage, vec!["sub_1"]);
+    fn accept_subscription_duplicate_in_batch() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
 
-        // accept pending sub as gp
+        let accept = AcceptSubscription {
+            subscription: Addr::unchecked("sub_1"),
+            commitment_in_capital: 20_000,
+            wire_reference: None,
+            max_commitment_override: None,
+            commitment_in_shares: None,
+        };
         let res = execute(
             deps.as_mut(),
             mock_env(),
-            mock_info("bad_actor", &[]),
+            mock_info("gp", &[]),
             HandleMsg::AcceptSubscriptions {
-                subscriptions: vec![AcceptSubscription {
-                    subscription: Addr::unchecked("sub_1"),
-                    commitment_in_capital: 20_000,
-                }]
-                .into_iter()
-                .collect(),
+                subscriptions: vec![accept.clone(), accept],
             },
         );
-        assert!(res.is_err());
+
+        // verify the batch is rejected before any subscription is accepted
+        assert_eq!(
+            "Generic error: duplicate subscription in batch",
+            res.unwrap_err().to_string()
+        );
+        assert_eq!(
+            None,
+            accepted_subscriptions_read(&deps.storage)
+                .may_load()
+                .unwrap()
+        );
+    }
+
+    fn accept_with_override(
+        commitment_in_capital: u64,
+        max_commitment_override: Option<u64>,
+    ) -> HandleMsg {
+        HandleMsg::AcceptSubscriptions {
+            subscriptions: vec![AcceptSubscription {
+                subscription: Addr::unchecked("sub_1"),
+                commitment_in_capital,
+                wire_reference: None,
+                max_commitment_override,
+                commitment_in_shares: None,
+            }],
+        }
     }
 
     #[test]
-    fn accept_subscription_with_bad_amount() {
-        let mut deps = mock_sub_state();
-        set_pending(&mut deps.storage, vec!["sub_1"]);
+    fn accept_subscription_with_commitment_override() {
+        let mut deps = default_deps(Some(|state| state.max_commitment = Some(10_000)));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            accept_with_override(20_000, Some(30_000)),
+        )
+        .unwrap();
+
+        // verify override replaces the raise max and is kept for capital calls
+        assert_eq!(
+            30_000,
+            commitment_cap_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_above_max_commitment() {
+        let mut deps = default_deps(Some(|state| state.max_commitment = Some(10_000)));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
 
-        // accept pending sub as gp
         let res = execute(
             deps.as_mut(),
             mock_env(),
             mock_info("gp", &[]),
-            HandleMsg::AcceptSubscriptions {
-                subscriptions: vec![AcceptSubscription {
-                    subscription: Addr::unchecked("sub_1"),
-                    commitment_in_capital: 20_001,
-                }]
-                .into_iter()
-                .collect(),
-            },
+            accept_with_override(20_000, None),
+        );
+        assert_eq!(
+            "commitment must be at most 10000",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_above_commitment_override() {
+        let mut deps = default_deps(Some(|state| state.max_commitment = Some(100_000)));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            accept_with_override(20_000, Some(10_000)),
+        );
+        assert_eq!(
+            "commitment must be at most 10000",
+            res.unwrap_err().to_string()
+        );
+        assert_eq!(
+            None,
+            commitment_cap_storage_read(&deps.storage)
+                .may_load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    fn accept_in(commitment_in_capital: u64, commitment_in_shares: Option<u64>) -> HandleMsg {
+        HandleMsg::AcceptSubscriptions {
+            subscriptions: vec![AcceptSubscription {
+                subscription: Addr::unchecked("sub_1"),
+                commitment_in_capital,
+                commitment_in_shares,
+                wire_reference: None,
+                max_commitment_override: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn accept_subscription_in_shares_matches_capital() {
+        let accept = |msg: HandleMsg| {
+            let mut deps = default_deps(None);
+            set_eligible(&mut deps.storage, vec!["sub_1"]);
+            let res = execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), msg).unwrap();
+            let exchanges = asset_exchange_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap();
+            (res.attributes, exchanges)
+        };
+
+        // verify shares and their capital equivalent record the same commitment
+        let by_capital = accept(accept_in(20_000, None));
+        let by_shares = accept(accept_in(0, Some(200)));
+        assert_eq!(by_capital, by_shares);
+        assert_eq!(Some(200), by_shares.1[0].commitment_in_shares);
+    }
+
+    #[test]
+    fn accept_subscription_in_both_capital_and_shares() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            accept_in(20_000, Some(200)),
+        );
+        assert_eq!(
+            "Generic error: accept must be in either capital or shares, not both",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_capital_conversion_overflow() {
+        let mut deps = default_deps(Some(|state| state.capital_per_share = 1));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            accept_with_override(u64::MAX - 1, None),
+        );
+        assert_eq!(
+            "Generic error: capital conversion overflow",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_mints_commitment() {
+        let mut deps = default_deps(None);
+        let mut state = State::test_default();
+        state.mint_commitment_on_accept = true;
+        config(&mut deps.storage).save(&state).unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }]
+                .into_iter()
+                .collect(),
+            },
+        )
+        .unwrap();
+
+        // verify commitment is minted and withdrawn to the sub
+        assert_eq!(2, res.messages.len());
+        assert_eq!(
+            &Coin::new(200, "commitment_coin"),
+            mint_args(msg_at_index(&res, 0))
+        );
+        let (marker_denom, coin, recipient) = withdraw_args(msg_at_index(&res, 1));
+        assert_eq!("commitment_coin", marker_denom);
+        assert_eq!(&Coin::new(200, "commitment_coin"), coin);
+        assert_eq!(&Addr::unchecked("sub_1"), recipient);
+
+        // verify no outstanding commitment exchange is recorded
+        assert_eq!(
+            None,
+            asset_exchange_storage_read(&deps.storage)
+                .may_load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn capital_call_after_accept_with_minted_commitment() {
+        let mut deps = default_deps(None);
+        let mut state = State::test_default();
+        state.mint_commitment_on_accept = true;
+        config(&mut deps.storage).save(&state).unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+        deps.querier
+            .base
+            .update_balance(Addr::unchecked("sub_1"), coins(200, "commitment_coin"));
+
+        // verify calls are drawn against the minted commitment
+        let call = |amount| HandleMsg::IssueCapitalCalls {
+            calls: vec![CapitalCall {
+                subscription: Addr::unchecked("sub_1"),
+                amount,
+            }],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("gp", &[]), call(5_000)).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            call(20_000),
+        );
+        assert_eq!(
+            "capital call exceeds remaining committed shares",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_missing_acceptable_accreditation() {
+        let mut deps = mock_sub_state();
+
+        let mut state = State::test_default();
+        state.acceptable_accreditations = vec![String::from("506c")].into_iter().collect();
+        config(&mut deps.storage).save(&state).unwrap();
+
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        // accept pending sub as gp
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }]
+                .into_iter()
+                .collect(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn accept_subscription_with_bad_amount() {
+        let mut deps = mock_sub_state();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        // accept pending sub as gp
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_001,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }]
+                .into_iter()
+                .collect(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn accept_subscription_with_wire_reference() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        // accept eligible sub as gp with a wire reference
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: Some(String::from("FED20230101ABC")),
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+
+        // verify wire reference can be retrieved
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetWireReference {
+                subscription: Addr::unchecked("sub_1"),
+            },
+        )
+        .unwrap();
+        let wire_reference: Option<String> = from_binary(&res).unwrap();
+        assert_eq!(Some(String::from("FED20230101ABC")), wire_reference);
+    }
+
+    #[test]
+    fn accept_subscription_with_wire_reference_too_long() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        // accept eligible sub as gp with an oversized wire reference
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: Some("X".repeat(65)),
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn accept_subscription_for_different_raise() {
+        let mut deps = wasm_smart_mock_dependencies(&[], |_, _| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&SubState {
+                    admin: Addr::unchecked("marketpalace"),
+                    lp: Addr::unchecked("lp"),
+                    raise: Addr::unchecked("raise_2"),
+                    commitment_denom: String::from("raise_2.commitment"),
+                    investment_denom: String::from("raise_2.investment"),
+                    capital_denom: String::from("stable_coin"),
+                    capital_per_share: 1,
+                })
+                .unwrap(),
+            ))
+        });
+        deps.querier.base.with_attributes("lp", &[("506c", "", "")]);
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }]
+                .into_iter()
+                .collect(),
+            },
+        );
+        match res.unwrap_err() {
+            ContractError::SubscriptionNotFound { msg } => {
+                assert_eq!("subscription belongs to a different raise", msg)
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        // verify no asset exchange is recorded for the subscription
+        assert_eq!(
+            None,
+            asset_exchange_storage_read(&deps.storage)
+                .may_load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_matching_proposed_commitment() {
+        let mut deps = default_deps(Some(|state| {
+            state.commitment_match_tolerance_bps = Some(100);
+        }));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        proposed_commitment_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &20_100)
+            .unwrap();
+
+        // accept within 1% of the proposed commitment
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+
+        // verify the sub is accepted
+        assert!(accepted_subscriptions_read(&deps.storage)
+            .load()
+            .unwrap()
+            .contains(&Addr::unchecked("sub_1")));
+    }
+
+    #[test]
+    fn accept_subscription_mismatching_proposed_commitment() {
+        let mut deps = default_deps(Some(|state| {
+            state.commitment_match_tolerance_bps = Some(100);
+        }));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        proposed_commitment_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &30_000)
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn accept_subscription_partially() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        proposed_commitment_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &30_000)
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+
+        // verify partial acceptance is flagged and the delta stored
+        assert_eq!(
+            vec![
+                Attribute::new("action", "accept_subscription"),
+                Attribute::new("subscription", "sub_1"),
+                Attribute::new("commitment_in_capital", "20000"),
+                Attribute::new("commitment_in_shares", "200"),
+                Attribute::new("partial_acceptance", "true"),
+            ],
+            res.attributes
+        );
+        assert_eq!(
+            10_000,
+            unaccepted_commitment_storage_read(&deps.storage)
+                .load(Addr::unchecked("sub_1").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn accept_subscription_above_proposed_rechecks_accreditation() {
+        let mut deps = mock_sub_state();
+        deps.querier.base.with_attributes("lp", &[("506b", "", "")]);
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        proposed_commitment_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &10_000)
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn accept_subscription_zero() {
+        let mut deps = default_deps(None);
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 0,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn accept_subscription_on_increment() {
+        let mut deps = default_deps(Some(|state| state.min_accept_increment = Some(1_000)));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_000,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        )
+        .unwrap();
+
+        // verify the sub is accepted
+        assert!(accepted_subscriptions_read(&deps.storage)
+            .load()
+            .unwrap()
+            .contains(&Addr::unchecked("sub_1")));
+    }
+
+    #[test]
+    fn accept_subscription_off_increment() {
+        let mut deps = default_deps(Some(|state| state.min_accept_increment = Some(1_000)));
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        // evenly divisible by capital per share, but not a round lot
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::AcceptSubscriptions {
+                subscriptions: vec![AcceptSubscription {
+                    subscription: Addr::unchecked("sub_1"),
+                    commitment_in_capital: 20_500,
+                    wire_reference: None,
+                    max_commitment_override: None,
+                    commitment_in_shares: None,
+                }],
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reevaluate_all_pending() {
+        // each subscription resolves to its own lp
+        let mut deps = wasm_smart_mock_dependencies(&[], |contract, _| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&SubState {
+                    admin: Addr::unchecked("marketpalace"),
+                    lp: Addr::unchecked(format!("lp_{}", contract)),
+                    raise: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                    commitment_denom: String::from("raise_1.commitment"),
+                    investment_denom: String::from("raise_1.investment"),
+                    capital_denom: String::from("stable_coin"),
+                    capital_per_share: 1,
+                })
+                .unwrap(),
+            ))
+        });
+        deps.querier
+            .base
+            .with_attributes("lp_sub_1", &[("506c", "", "")]);
+        deps.querier
+            .base
+            .with_attributes("lp_sub_2", &[("506b", "", "")]);
+        deps.querier
+            .base
+            .with_attributes("lp_sub_3", &[("506c", "", "")]);
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1", "sub_2", "sub_3"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::ReevaluateAllPending {},
+        )
+        .unwrap();
+
+        // verify evaluated and promoted counts
+        assert_eq!("3", res.attributes[0].value);
+        assert_eq!("2", res.attributes[1].value);
+
+        // verify only the accredited lps were promoted
+        assert_eq!(
+            to_addresses(vec!["sub_2"]),
+            pending_subscriptions_read(&deps.storage).load().unwrap()
+        );
+        assert_eq!(
+            to_addresses(vec!["sub_1", "sub_3"]),
+            eligible_subscriptions_read(&deps.storage).load().unwrap()
+        );
+    }
+
+    #[test]
+    fn update_accreditations() {
+        // each subscription resolves to its own lp
+        let mut deps = wasm_smart_mock_dependencies(&[], |contract, _| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&SubState {
+                    admin: Addr::unchecked("marketpalace"),
+                    lp: Addr::unchecked(format!("lp_{}", contract)),
+                    raise: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                    commitment_denom: String::from("raise_1.commitment"),
+                    investment_denom: String::from("raise_1.investment"),
+                    capital_denom: String::from("stable_coin"),
+                    capital_per_share: 1,
+                })
+                .unwrap(),
+            ))
+        });
+        deps.querier
+            .base
+            .with_attributes("lp_sub_1", &[("506c", "", "")]);
+        deps.querier
+            .base
+            .with_attributes("lp_sub_2", &[("506b", "", "")]);
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+        set_pending(&mut deps.storage, vec!["sub_2"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: vec![String::from("506b")].into_iter().collect(),
+                start_after: None,
+            },
+        )
+        .unwrap();
+
+        // verify both subs swapped classification
+        assert_eq!("reclassified", res.attributes[0].key);
+        assert_eq!("2", res.attributes[0].value);
+        assert_eq!(
+            to_addresses(vec!["sub_1"]),
+            pending_subscriptions_read(&deps.storage).load().unwrap()
+        );
+        assert_eq!(
+            to_addresses(vec!["sub_2"]),
+            eligible_subscriptions_read(&deps.storage).load().unwrap()
+        );
+        assert_eq!(
+            vec![String::from("506b")]
+                .into_iter()
+                .collect::<HashSet<_>>(),
+            config_read(&deps.storage)
+                .load()
+                .unwrap()
+                .acceptable_accreditations
+        );
+    }
+
+    #[test]
+    fn update_accreditations_in_batches() {
+        let mut deps = wasm_smart_mock_dependencies(&[], |_, _| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&SubState {
+                    admin: Addr::unchecked("marketpalace"),
+                    lp: Addr::unchecked("lp"),
+                    raise: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                    commitment_denom: String::from("raise_1.commitment"),
+                    investment_denom: String::from("raise_1.investment"),
+                    capital_denom: String::from("stable_coin"),
+                    capital_per_share: 1,
+                })
+                .unwrap(),
+            ))
+        });
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        let subscriptions: Vec<String> = (0..30).map(|i| format!("sub_{:02}", i)).collect();
+        set_pending(
+            &mut deps.storage,
+            subscriptions.iter().map(String::as_str).collect(),
+        );
+
+        // with no accreditations required every sub becomes eligible
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: HashSet::new(),
+                start_after: None,
+            },
+        )
+        .unwrap();
+
+        // verify only the first batch was evaluated
+        assert_eq!(
+            vec![
+                Attribute::new("reclassified", "25"),
+                Attribute::new("evaluated", "25"),
+                Attribute::new("remaining", "5"),
+            ],
+            res.attributes
+        );
+        assert_eq!(
+            5,
+            pending_subscriptions_read(&deps.storage)
+                .load()
+                .unwrap()
+                .len()
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: HashSet::new(),
+                start_after: Some(Addr::unchecked("sub_24")),
+            },
+        )
+        .unwrap();
+
+        // verify the rest are evaluated from where the first batch stopped
+        assert_eq!(
+            vec![
+                Attribute::new("reclassified", "5"),
+                Attribute::new("evaluated", "5"),
+                Attribute::new("remaining", "0"),
+            ],
+            res.attributes
+        );
+        assert!(pending_subscriptions_read(&deps.storage)
+            .load()
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            30,
+            eligible_subscriptions_read(&deps.storage)
+                .load()
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn update_accreditations_with_accreditation_groups() {
+        let res = execute(
+            default_deps(Some(|state| {
+                state.accreditation_groups = vec![vec![String::from("506c")].into_iter().collect()]
+            }))
+            .as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: HashSet::new(),
+                start_after: None,
+            },
+        );
+        assert_eq!(
+            "Generic error: accreditations are resolved from the accreditation source or groups",
+            res.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn update_accreditations_with_accreditation_source() {
+        let res = execute(
+            default_deps(Some(|state| {
+                state.accreditation_source = Some(Addr::unchecked("accreditation_source"))
+            }))
+            .as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: HashSet::new(),
+                start_after: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn repair_membership() {
+        let mut deps = default_deps(None);
+        set_pending(&mut deps.storage, vec!["sub_1", "sub_2", "sub_4"]);
+        set_eligible(&mut deps.storage, vec!["sub_2", "sub_3"]);
+        set_accepted(&mut deps.storage, vec!["sub_1", "sub_3"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("marketpalace", &[]),
+            HandleMsg::RepairMembership {},
+        )
+        .unwrap();
+
+        // verify each correction is logged
+        assert_eq!(
+            vec![
+                Attribute::new("removed_from_pending", "sub_1"),
+                Attribute::new("removed_from_pending", "sub_2"),
+                Attribute::new("removed_from_eligible", "sub_3"),
+            ],
+            res.attributes
+        );
+
+        // verify each sub is left in its furthest along set
+        assert_eq!(
+            to_addresses(vec!["sub_4"]),
+            pending_subscriptions_read(&deps.storage).load().unwrap()
+        );
+        assert_eq!(
+            to_addresses(vec!["sub_2"]),
+            eligible_subscriptions_read(&deps.storage).load().unwrap()
+        );
+        assert_eq!(
+            to_addresses(vec!["sub_1", "sub_3"]),
+            accepted_subscriptions_read(&deps.storage).load().unwrap()
+        );
+    }
+
+    #[test]
+    fn repair_membership_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::RepairMembership {},
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn withdraw_proposal() {
+        let mut deps = mock_sub_state();
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+        proposed_commitment_storage(&mut deps.storage)
+            .save(Addr::unchecked("sub_1").as_bytes(), &20_000)
+            .unwrap();
+        lp_to_subscription(&mut deps.storage)
+            .save(
+                Addr::unchecked("lp").as_bytes(),
+                &to_addresses(vec!["sub_1"]),
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::WithdrawProposal {},
+        )
+        .unwrap();
+
+        // verify the sub is no longer pending
+        assert_eq!(
+            0,
+            pending_subscriptions_read(&deps.storage)
+                .load()
+                .unwrap()
+                .len()
+        );
+        assert!(proposed_commitment_storage_read(&deps.storage)
+            .may_load(Addr::unchecked("sub_1").as_bytes())
+            .unwrap()
+            .is_none());
+        assert!(lp_to_subscription_read(&deps.storage)
+            .may_load(Addr::unchecked("lp").as_bytes())
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            vec![
+                Attribute::new("action", "withdraw_proposal"),
+                Attribute::new("subscription", "sub_1"),
+            ],
+            res.attributes
+        );
+    }
+
+    #[test]
+    fn withdraw_proposal_uses_lp_mapping() {
+        // no wasm querier, so the subscription must be resolved without smart queries
+        let mut deps = default_deps(None);
+        set_pending(&mut deps.storage, vec!["sub_1", "sub_2"]);
+        lp_to_subscription(&mut deps.storage)
+            .save(
+                Addr::unchecked("lp").as_bytes(),
+                &to_addresses(vec!["sub_2", "sub_3"]),
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::WithdrawProposal {},
+        )
+        .unwrap();
+
+        // verify only the lp's subscription is withdrawn
+        assert_eq!(Attribute::new("subscription", "sub_2"), res.attributes[1]);
+        assert_eq!(
+            to_addresses(vec!["sub_1"]),
+            pending_subscriptions_read(&deps.storage).load().unwrap()
+        );
+        // verify the withdrawn sub is no longer mapped to the lp
+        assert_eq!(
+            to_addresses(vec!["sub_3"]),
+            lp_to_subscription_read(&deps.storage)
+                .load(Addr::unchecked("lp").as_bytes())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn withdraw_proposal_eligible() {
+        let mut deps = mock_sub_state();
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_eligible(&mut deps.storage, vec!["sub_1"]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::WithdrawProposal {},
+        )
+        .unwrap();
+
+        // verify the sub is no longer eligible
+        assert_eq!(
+            0,
+            eligible_subscriptions_read(&deps.storage)
+                .load()
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn withdraw_proposal_not_owner() {
+        let mut deps = mock_sub_state();
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::WithdrawProposal {},
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn withdraw_proposal_already_accepted() {
+        let mut deps = mock_sub_state();
+        config(&mut deps.storage)
+            .save(&State::test_default())
+            .unwrap();
+        set_accepted(&mut deps.storage, vec!["sub_1"]);
+
+        // verify an accepted sub can't be withdrawn
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::WithdrawProposal {},
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn update_accreditations_bad_actor() {
+        let res = execute(
+            default_deps(None).as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: HashSet::new(),
+                start_after: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn update_accreditations_config_locked() {
+        let res = execute(
+            default_deps(Some(|state| state.config_locked = true)).as_mut(),
+            mock_env(),
+            mock_info("gp", &[]),
+            HandleMsg::UpdateAccreditations {
+                accreditations: HashSet::new(),
+                start_after: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reevaluate_all_pending_bad_actor() {
+        let mut deps = default_deps(None);
+        set_pending(&mut deps.storage, vec!["sub_1"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bad_actor", &[]),
+            HandleMsg::ReevaluateAllPending {},
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn propose_subscription_with_accreditation_source() {
+        let mut deps = wasm_smart_mock_dependencies(&[], |contract, _| {
+            assert_eq!("accreditation_source", contract);
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&vec![String::from("506c")]).unwrap(),
+            ))
+        });
+        deps.querier.base.with_attributes("lp", &[("506c", "", "")]);
+
+        // the static set alone would leave the lp pending
+        let mut state = State::test_default();
+        state.acceptable_accreditations = vec![String::from("506b")].into_iter().collect();
+        state.accreditation_source = Some(Addr::unchecked("accreditation_source"));
+        config(&mut deps.storage).save(&state).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("lp", &[]),
+            HandleMsg::ProposeSubscription {
+                initial_commitment: None,
+            },
+        )
+        .unwrap();
+
+        // verify eligibility comes from the source's accreditations
+        assert_eq!(
+            "true",
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "eligible")
+                .unwrap()
+                .value
         );
-        assert!(res.is_err());
     }
 }